@@ -1,162 +1,2470 @@
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+
+use serde_json::{json, Value};
 
 use websockets::WebSocket;
 
-use std::sync::Arc;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Context;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use ethers::{
+    prelude::{LocalWallet, Ws},
+    providers::{Middleware, Provider},
+    signers::Signer,
+    types::{Signature, H256},
+};
+
+use alloy_sol_types::{Eip712Domain, SolStruct};
+
+use alloy_primitives::{keccak256, Address, FixedBytes, U256};
+
+use aori_types::{
+    builder::{counter_order, private_to},
+    constants::{MARKET_FEED_URL, REQUEST_URL},
+    permit::Permit,
+    salt::RandomSalt,
+    seaport::{seaport_domain, seaport_domain_for, verify_order_hash, OrderComponents},
+    time::check_order_timestamps,
+};
+
+use futures::{stream, Stream};
+
+use crate::{
+    error::AoriError,
+    rate_limit::RateLimiter,
+    response::{AoriResponse, Orderbook, OrderbookEvent, RestingOrder},
+    signer::{RawDigest, TypedData},
+    ws_backend::WsBackend,
+};
+
+/// Reads the `exp` claim of a JWT without verifying its signature.
+fn jwt_expiry(jwt: &str) -> eyre::Result<SystemTime> {
+    let payload = jwt
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| eyre::eyre!("auth token is not a JWT"))?;
+    let claims: Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload.trim_end_matches('='))?)?;
+    let exp = claims
+        .get("exp")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| eyre::eyre!("auth token has no exp claim"))?;
+    Ok(UNIX_EPOCH + Duration::from_secs(exp))
+}
+
+/// Whether an order posted with `make_order_with_visibility` is broadcast. Who can
+/// fill an order is decided by the order itself; see `make_private_order`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderVisibility {
+    /// Broadcast on the public orderbook.
+    #[default]
+    Public,
+    /// Kept off the public orderbook.
+    Private,
+}
+
+impl OrderVisibility {
+    /// Writes this visibility's `isPublic` flag into the `aori_makeOrder` params object.
+    pub fn apply(&self, params: &mut Value) {
+        params["isPublic"] = (*self == OrderVisibility::Public).into();
+    }
+}
+
+/// Trading state of a market as reported by `aori_marketStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarketStatus {
+    Active,
+    Halted,
+    Closed,
+}
+
+/// Which side of the book `view_orderbook_filtered` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Price order of the orders `view_orderbook_filtered` returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Everything that went into signing one order, for operators who must keep an
+/// audit trail of exactly what was signed.
+#[derive(Debug, Clone)]
+pub struct SigningRecord {
+    pub domain: Eip712Domain,
+    pub domain_separator: FixedBytes<32>,
+    pub struct_hash: FixedBytes<32>,
+    pub digest: FixedBytes<32>,
+    pub signature: Signature,
+}
+
+impl SigningRecord {
+    pub fn new(order: &OrderComponents, domain: &Eip712Domain, signature: Signature) -> Self {
+        Self {
+            domain: domain.clone(),
+            domain_separator: domain.separator(),
+            struct_hash: order.eip712_hash_struct(),
+            digest: order.eip712_signing_hash(domain),
+            signature,
+        }
+    }
+}
+
+/// Receives a `SigningRecord` for every order a provider signs.
+pub type AuditHook = Arc<dyn Fn(&SigningRecord) + Send + Sync>;
+
+/// How `AoriProvider` re-dials after the server drops a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Attempts after the first before giving up.
+    pub max_retries: u32,
+    /// Delay before the first retry; each later retry waits one more `backoff`.
+    pub backoff: Duration,
+}
+
+fn subscribe_orderbook_params(with_snapshot: bool) -> Value {
+    json!([{ "snapshot": with_snapshot }])
+}
+
+/// Subscription params carrying only the filters that are set.
+fn filtered_subscribe_params(
+    base: Option<&str>,
+    quote: Option<&str>,
+    chain_id: Option<u64>,
+) -> Value {
+    let mut filters = json!({});
+    if let Some(base) = base {
+        filters["base"] = base.into();
+    }
+    if let Some(quote) = quote {
+        filters["quote"] = quote.into();
+    }
+    if let Some(chain_id) = chain_id {
+        filters["chainId"] = chain_id.into();
+    }
+    json!([filters])
+}
+
+/// `aori_viewOrderbook` query carrying only the filters that are set.
+fn orderbook_query(
+    base: &str,
+    quote: &str,
+    side: Option<Side>,
+    limit: Option<u32>,
+    sort: Option<SortOrder>,
+) -> Value {
+    let mut query = json!({
+        "base": base,
+        "quote": quote,
+    });
+    if let Some(side) = side {
+        query["side"] = json!(side);
+    }
+    if let Some(limit) = limit {
+        query["limit"] = limit.into();
+    }
+    if let Some(sort) = sort {
+        query["sort"] = json!(sort);
+    }
+    query
+}
+
+/// What a taker receives and pays filling `order`: its first offer item, and every
+/// consideration item in the token of the first one, so fees in the same currency count
+/// against the price.
+fn fill_amounts(order: &OrderComponents) -> (U256, U256) {
+    let receives = order
+        .offer
+        .first()
+        .map_or(U256::ZERO, |item| item.startAmount);
+    let pays = order.consideration.first().map_or(U256::ZERO, |first| {
+        order
+            .consideration
+            .iter()
+            .filter(|item| item.token == first.token)
+            .fold(U256::ZERO, |total, item| {
+                total.saturating_add(item.startAmount)
+            })
+    });
+    (receives, pays)
+}
+
+/// Compares resting orders by the rate a taker fills them at, amount received per
+/// amount paid, cross-multiplying so no precision is lost. Greater is better on either
+/// side of the book: filling an ask receives base for quote and filling a bid receives
+/// quote for base, so the cheapest ask and the highest bid both have the highest rate.
+fn fill_rate_cmp(a: &OrderComponents, b: &OrderComponents) -> Ordering {
+    let (a_receives, a_pays) = fill_amounts(a);
+    let (b_receives, b_pays) = fill_amounts(b);
+    a_receives
+        .saturating_mul(b_pays)
+        .cmp(&b_receives.saturating_mul(a_pays))
+}
+
+/// Most replies `AoriProvider` holds for requests that haven't asked for them yet. Past
+/// this the oldest are dropped.
+pub const MAX_PENDING_REPLIES: usize = 1024;
+
+/// Awaits `reply`, failing with `AoriError::Timeout` if `timeout` passes first.
+async fn within<T>(
+    timeout: Option<Duration>,
+    reply: impl Future<Output = eyre::Result<T>>,
+) -> eyre::Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, reply)
+            .await
+            .map_err(|_| AoriError::Timeout(timeout))?,
+        None => reply.await,
+    }
+}
+
+/// Rejects orders whose `endTime - startTime` is longer than `max_lifetime` seconds.
+fn check_order_lifetime(order: &OrderComponents, max_lifetime: u64) -> eyre::Result<()> {
+    let lifetime = order.endTime.saturating_sub(order.startTime);
+    if lifetime > U256::from(max_lifetime) {
+        eyre::bail!(
+            "order lifetime of {}s exceeds the maximum of {}s",
+            lifetime,
+            max_lifetime
+        );
+    }
+    Ok(())
+}
+
+/// Client for the Aori request and feed connections. `S` signs orders and auth messages;
+/// any ethers `Signer` works, so keys can stay in a KMS, hardware wallet or remote
+/// signing service.
+pub struct AoriProvider<B = WebSocket, S = LocalWallet> {
+    pub request_conn: B,
+    pub feed_conn: B,
+    pub wallet: S,
+    pub chain_id: u64,
+    pub seaport_domain: Eip712Domain,
+    /// Id of the last request built. It only ever increases, across reconnects too, so
+    /// no two requests from one provider share an id and a late reply from before a
+    /// reconnect can't be mistaken for a reply to a newer request.
+    pub last_id: u64,
+    /// Value of the `jsonrpc` field on every outbound request, "2.0" by default.
+    pub jsonrpc_version: Arc<str>,
+    pub wallet_addr: Arc<str>,
+    pub wallet_sig: Arc<str>,
+    /// Longest lifetime in seconds `make_order` will post, as a guard against forgotten
+    /// long-lived quotes. `None`, the default, disables the check; set with
+    /// `with_max_order_lifetime`.
+    pub max_order_lifetime: Option<u64>,
+    /// Called with a `SigningRecord` for every order signed. Off by default; set with
+    /// `with_audit_hook`.
+    pub audit_hook: Option<AuditHook>,
+    /// Seaport counter last read by `fetch_counter`, for populating `OrderComponents`.
+    pub counter: Option<U256>,
+    /// Replies read off the request connection while waiting for a different id,
+    /// held until their own request asks for them. Capped at `MAX_PENDING_REPLIES`.
+    pending_replies: BTreeMap<u64, Value>,
+    /// Method of each request sent on the request connection whose reply hasn't been
+    /// handed out yet, so `recv_response` can classify the reply by what was asked.
+    /// Capped at `MAX_PENDING_REPLIES`.
+    request_methods: BTreeMap<u64, String>,
+    /// Ids of keepalive pings whose pongs are discarded rather than handed out.
+    /// Capped at `MAX_PENDING_REPLIES`.
+    keepalive_ids: BTreeSet<u64>,
+    /// Set with `with_auto_reconnect`. `None` leaves a dropped connection dead.
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// Re-run `auth_wallet` and `check_auth` before an order request once the JWT has
+    /// expired.
+    pub auto_reauth: bool,
+    /// Params of the last orderbook subscription, replayed on reconnect.
+    orderbook_subscription: Option<Value>,
+    /// Whether `subscribe_account` was called, so it is replayed on reconnect too.
+    account_subscribed: bool,
+    /// JWT from the last `auth_wallet`, and when it expires if it says.
+    auth_token: Option<String>,
+    auth_expiry: Option<SystemTime>,
+    /// Where `reconnect` re-dials the request and feed connections.
+    pub request_url: Arc<str>,
+    pub feed_url: Arc<str>,
+    /// Set with `with_rate_limit`; the relayer caps each connection separately.
+    request_limiter: Option<RateLimiter>,
+    feed_limiter: Option<RateLimiter>,
+    /// How long `wait_for_reply` and `recv_response` wait before giving up with
+    /// `AoriError::Timeout`. `None` waits forever.
+    pub request_timeout: Option<Duration>,
+}
+
+impl AoriProvider {
+    /// Builds a provider from `PRIVATE_KEY` and `NODE_URL`. The wallet address is
+    /// derived from the key; `WALLET_ADDRESS`, if set, must agree with it.
+    pub async fn new_from_env() -> eyre::Result<Self> {
+        let key = std::env::var("PRIVATE_KEY").context("missing PRIVATE_KEY")?;
+        let node = std::env::var("NODE_URL").context("missing NODE_URL")?;
+
+        if let Ok(address) = std::env::var("WALLET_ADDRESS") {
+            check_wallet_address(&key.parse::<LocalWallet>()?, &address)?;
+        }
+        AoriProviderBuilder::new()
+            .private_key(key)
+            .node_url(node)
+            .build()
+            .await
+    }
+}
+
+/// Errors unless `expected` is the address of `wallet`, so a stale `WALLET_ADDRESS`
+/// can't silently disagree with the key that signs.
+fn check_wallet_address(wallet: &LocalWallet, expected: &str) -> eyre::Result<()> {
+    let expected: ethers::types::Address = expected
+        .parse()
+        .context("WALLET_ADDRESS is not a valid address")?;
+    if expected != wallet.address() {
+        eyre::bail!(
+            "WALLET_ADDRESS {:?} does not match the address of PRIVATE_KEY {:?}",
+            expected,
+            wallet.address()
+        );
+    }
+    Ok(())
+}
+
+/// Configures an `AoriProvider` without going through environment variables. The wallet
+/// address is always derived from the private key or signer.
+#[derive(Clone)]
+pub struct AoriProviderBuilder {
+    private_key: Option<String>,
+    node_url: Option<String>,
+    chain_id: Option<u64>,
+    request_url: String,
+    feed_url: String,
+    reconnect_policy: Option<ReconnectPolicy>,
+    auto_reauth: bool,
+    rate_limit: Option<u32>,
+    request_timeout: Option<Duration>,
+    jsonrpc_version: String,
+    audit_hook: Option<AuditHook>,
+    max_order_lifetime: Option<u64>,
+    verifying_contract: Option<Address>,
+}
+
+impl Default for AoriProviderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AoriProviderBuilder {
+    pub fn new() -> Self {
+        Self {
+            private_key: None,
+            node_url: None,
+            chain_id: None,
+            request_url: REQUEST_URL.to_string(),
+            feed_url: MARKET_FEED_URL.to_string(),
+            reconnect_policy: None,
+            auto_reauth: false,
+            rate_limit: None,
+            request_timeout: None,
+            jsonrpc_version: "2.0".to_string(),
+            audit_hook: None,
+            max_order_lifetime: None,
+            verifying_contract: None,
+        }
+    }
+
+    pub fn private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.private_key = Some(private_key.into());
+        self
+    }
+
+    /// Node to read the chain id from. Not needed when `chain_id` is set.
+    pub fn node_url(mut self, node_url: impl Into<String>) -> Self {
+        self.node_url = Some(node_url.into());
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    pub fn request_url(mut self, request_url: impl Into<String>) -> Self {
+        self.request_url = request_url.into();
+        self
+    }
+
+    pub fn feed_url(mut self, feed_url: impl Into<String>) -> Self {
+        self.feed_url = feed_url.into();
+        self
+    }
+
+    pub fn with_auto_reconnect(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.reconnect_policy = Some(ReconnectPolicy {
+            max_retries,
+            backoff,
+        });
+        self
+    }
+
+    /// Transparently re-authenticates before order requests once the JWT expires.
+    pub fn with_auto_reauth(mut self, auto_reauth: bool) -> Self {
+        self.auto_reauth = auto_reauth;
+        self
+    }
+
+    /// Limits each connection to `per_second` outbound messages a second.
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        self.rate_limit = Some(per_second);
+        self
+    }
+
+    /// Fails awaited replies with `AoriError::Timeout` after `timeout`.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Value of the `jsonrpc` field on every outbound request, "2.0" by default.
+    pub fn jsonrpc_version(mut self, jsonrpc_version: impl Into<String>) -> Self {
+        self.jsonrpc_version = jsonrpc_version.into();
+        self
+    }
+
+    /// Calls `hook` with a `SigningRecord` for every order signed, for operators who
+    /// must keep an audit trail.
+    pub fn with_audit_hook(
+        mut self,
+        hook: impl Fn(&SigningRecord) + Send + Sync + 'static,
+    ) -> Self {
+        self.audit_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Rejects orders that live longer than `max_lifetime`, rounded down to seconds.
+    pub fn with_max_order_lifetime(mut self, max_lifetime: Duration) -> Self {
+        self.max_order_lifetime = Some(max_lifetime.as_secs());
+        self
+    }
+
+    /// Signs orders for a Seaport deployment at `verifying_contract` instead of the
+    /// canonical one. `build` fails for the zero address.
+    pub fn with_verifying_contract(mut self, verifying_contract: Address) -> Self {
+        self.verifying_contract = Some(verifying_contract);
+        self
+    }
+
+    pub async fn build(self) -> eyre::Result<AoriProvider> {
+        self.build_with_backend().await
+    }
+
+    /// Like `build`, connecting with a `WsBackend` other than the default.
+    pub async fn build_with_backend<B: WsBackend>(self) -> eyre::Result<AoriProvider<B>> {
+        let wallet = self
+            .private_key
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("no private key set"))?
+            .parse::<LocalWallet>()?;
+        self.build_with(wallet).await
+    }
+
+    /// Like `build`, signing with `signer` instead of a private key. Any `private_key`
+    /// set is ignored.
+    pub async fn build_with_signer<S: Signer + 'static>(
+        self,
+        signer: S,
+    ) -> eyre::Result<AoriProvider<WebSocket, S>> {
+        self.build_with(signer).await
+    }
+
+    async fn build_with<B: WsBackend, S: Signer + 'static>(
+        self,
+        wallet: S,
+    ) -> eyre::Result<AoriProvider<B, S>> {
+        let chain_id = match (self.chain_id, &self.node_url) {
+            (Some(chain_id), _) => chain_id,
+            (None, Some(node_url)) => {
+                let pv = Provider::<Ws>::connect(node_url).await?;
+                pv.get_chainid().await?.low_u64()
+            }
+            (None, None) => eyre::bail!("set either a chain id or a node url"),
+        };
+        let request_conn = B::connect(&self.request_url).await?;
+        let feed_conn = B::connect(&self.feed_url).await?;
+        let mut apv =
+            AoriProvider::from_connections(request_conn, feed_conn, wallet, chain_id).await?;
+        apv.request_url = self.request_url.into();
+        apv.feed_url = self.feed_url.into();
+        apv.reconnect_policy = self.reconnect_policy;
+        apv.auto_reauth = self.auto_reauth;
+        apv.request_timeout = self.request_timeout;
+        apv.jsonrpc_version = self.jsonrpc_version.into();
+        apv.audit_hook = self.audit_hook;
+        apv.max_order_lifetime = self.max_order_lifetime;
+        if let Some(verifying_contract) = self.verifying_contract {
+            apv.set_verifying_contract(verifying_contract)?;
+        }
+        if let Some(per_second) = self.rate_limit {
+            apv = apv.with_rate_limit(per_second);
+        }
+        Ok(apv)
+    }
+}
+
+impl<B: WsBackend + 'static, S: Signer + 'static> AoriProvider<B, S> {
+    /// Spawns a task that pings the request connection every `interval` so the server
+    /// doesn't close it as idle. Each ping takes the provider's lock and its id from
+    /// `last_id` like any other request, so it never reuses an in-flight id. The task
+    /// stops when a ping fails to send; abort the handle to stop it sooner.
+    pub fn spawn_keepalive(provider: Arc<Mutex<Self>>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let mut apv = provider.lock().await;
+                if apv.send_keepalive().await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+impl<B: WsBackend, S: Signer + 'static> AoriProvider<B, S> {
+    /// Builds a provider over already-connected request and feed sockets of any
+    /// `WsBackend`, authenticating as the address of `wallet`.
+    pub async fn from_connections(
+        request_conn: B,
+        feed_conn: B,
+        wallet: S,
+        chain_id: u64,
+    ) -> eyre::Result<Self> {
+        let wallet = wallet.with_chain_id(chain_id);
+        let address = ethers::utils::to_checksum(&wallet.address(), None);
+        let sig: Signature = wallet.sign_message(address.as_str()).await?;
+        Ok(Self::from_parts(
+            request_conn,
+            feed_conn,
+            wallet,
+            chain_id,
+            address,
+            sig,
+        ))
+    }
+
+    fn from_parts(
+        request_conn: B,
+        feed_conn: B,
+        wallet: S,
+        chain_id: u64,
+        address: String,
+        sig: Signature,
+    ) -> Self {
+        Self {
+            request_conn,
+            feed_conn,
+            wallet,
+            chain_id,
+            seaport_domain: seaport_domain(chain_id),
+            last_id: 0,
+            jsonrpc_version: "2.0".into(),
+            wallet_addr: address.into(),
+            wallet_sig: format!("0x{}", sig).into(),
+            max_order_lifetime: None,
+            audit_hook: None,
+            counter: None,
+            pending_replies: BTreeMap::new(),
+            request_methods: BTreeMap::new(),
+            keepalive_ids: BTreeSet::new(),
+            reconnect_policy: None,
+            auto_reauth: false,
+            auth_token: None,
+            auth_expiry: None,
+            request_url: REQUEST_URL.into(),
+            feed_url: MARKET_FEED_URL.into(),
+            orderbook_subscription: None,
+            account_subscribed: false,
+            request_limiter: None,
+            feed_limiter: None,
+            request_timeout: None,
+        }
+    }
+
+    /// Re-dials both connections when either is found closed, re-authenticating and
+    /// resubscribing to the orderbook, retrying up to `max_retries` times.
+    pub fn with_auto_reconnect(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.reconnect_policy = Some(ReconnectPolicy {
+            max_retries,
+            backoff,
+        });
+        self
+    }
+
+    /// Limits each connection to `per_second` outbound messages a second. Sends over
+    /// the limit wait their turn rather than fail.
+    pub fn with_rate_limit(mut self, per_second: u32) -> Self {
+        self.request_limiter = Some(RateLimiter::new(per_second));
+        self.feed_limiter = Some(RateLimiter::new(per_second));
+        self
+    }
+
+    /// Fails awaited replies with `AoriError::Timeout` once `timeout` passes without
+    /// one, so a silent relayer can't wedge the caller.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Rejects orders in `make_order` whose `endTime - startTime` is longer than
+    /// `max_lifetime`, rounded down to seconds.
+    pub fn with_max_order_lifetime(mut self, max_lifetime: Duration) -> Self {
+        self.max_order_lifetime = Some(max_lifetime.as_secs());
+        self
+    }
+
+    /// Calls `hook` with a `SigningRecord` for every order signed.
+    pub fn with_audit_hook(
+        mut self,
+        hook: impl Fn(&SigningRecord) + Send + Sync + 'static,
+    ) -> Self {
+        self.audit_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sends on the request connection once the rate limiter allows it.
+    async fn send_request_text(&mut self, payload: String) -> eyre::Result<()> {
+        if let Some(limiter) = &mut self.request_limiter {
+            limiter.acquire().await;
+        }
+        self.request_conn.send_text(payload).await
+    }
+
+    /// Sends on the feed connection once the rate limiter allows it.
+    async fn send_feed_text(&mut self, payload: String) -> eyre::Result<()> {
+        if let Some(limiter) = &mut self.feed_limiter {
+            limiter.acquire().await;
+        }
+        self.feed_conn.send_text(payload).await
+    }
+
+    /// Re-dials `request_url` and `feed_url`, runs `auth_wallet` and replays the
+    /// orderbook subscription if there was one. Without a reconnect policy this makes a
+    /// single attempt. Replies still pending on the old connection are dropped; request
+    /// ids carry on from `last_id` rather than restarting.
+    pub async fn reconnect(&mut self) -> eyre::Result<()> {
+        let policy = self.reconnect_policy.unwrap_or(ReconnectPolicy {
+            max_retries: 0,
+            backoff: Duration::ZERO,
+        });
+        let mut attempt = 0;
+        loop {
+            match self.redial().await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt >= policy.max_retries => {
+                    return Err(err.wrap_err(format!(
+                        "failed to reconnect after {} attempts",
+                        attempt + 1
+                    )))
+                }
+                Err(_) => {
+                    attempt += 1;
+                    tokio::time::sleep(policy.backoff * attempt).await;
+                }
+            }
+        }
+    }
+
+    /// One reconnect attempt. The reconnect policy is off while it runs, so a failure
+    /// re-authenticating or resubscribing fails this attempt instead of starting a
+    /// nested reconnect.
+    async fn redial(&mut self) -> eyre::Result<()> {
+        let policy = self.reconnect_policy.take();
+        let result = self.redial_once().await;
+        self.reconnect_policy = policy;
+        result
+    }
+
+    async fn redial_once(&mut self) -> eyre::Result<()> {
+        self.request_conn = B::connect(&self.request_url).await?;
+        self.feed_conn = B::connect(&self.feed_url).await?;
+        self.pending_replies.clear();
+        self.request_methods.clear();
+        self.keepalive_ids.clear();
+        Box::pin(self.auth_wallet()).await?;
+        if let Some(mut params) = self.orderbook_subscription.clone() {
+            // Updates were missed while disconnected, so always ask for a fresh snapshot.
+            params[0]["snapshot"] = true.into();
+            let sub_req = self.build_request("aori_subscribeOrderbook", params);
+            self.send_feed_text(sub_req.to_string()).await?;
+        }
+        if self.account_subscribed {
+            let sub_req = self.build_request("aori_subscribeAccount", self.account_params());
+            self.send_feed_text(sub_req.to_string()).await?;
+        }
+        Ok(())
+    }
+
+    /// Sends close frames on both connections and waits for the server to acknowledge
+    /// them. The feed connection is closed even if closing the request connection
+    /// fails; the first error is returned.
+    pub async fn close(mut self) -> eyre::Result<()> {
+        let request = self.request_conn.close().await;
+        let feed = self.feed_conn.close().await;
+        request.and(feed)
+    }
+
+    /// Sends on the request connection, reconnecting and resending once if it has
+    /// dropped and auto-reconnect is on. `ids` are the requests in `payload`; their
+    /// methods are kept through the reconnect so `recv_response` still classifies the
+    /// replies to the resent requests.
+    async fn send_request(&mut self, payload: String, ids: &[u64]) -> eyre::Result<()> {
+        if self.reconnect_policy.is_none() {
+            return self.send_request_text(payload).await;
+        }
+        if let Err(err) = self.send_request_text(payload.clone()).await {
+            let methods: Vec<(u64, String)> = ids
+                .iter()
+                .filter_map(|id| self.request_methods.remove_entry(id))
+                .collect();
+            self.reconnect()
+                .await
+                .wrap_err(format!("request connection dropped: {}", err))?;
+            self.request_methods.extend(methods);
+            self.send_request_text(payload).await?;
+        }
+        Ok(())
+    }
+
+    /// Receives on the request connection. If it has dropped and auto-reconnect is on,
+    /// reconnects before returning the error, since the awaited reply is lost.
+    /// Keepalive pongs are skipped.
+    async fn receive_request(&mut self) -> eyre::Result<Value> {
+        loop {
+            let resp: Value = match self.request_conn.receive_text().await {
+                Ok(payload) => serde_json::from_str(&payload)?,
+                Err(err) if self.reconnect_policy.is_some() => {
+                    self.reconnect().await?;
+                    return Err(err.wrap_err("request connection dropped before the reply arrived"));
+                }
+                Err(err) => return Err(err),
+            };
+            let resp = match resp {
+                // A reply to a batch: hand out the first and hold the rest back like any
+                // reply that arrives before it's asked for.
+                Value::Array(replies) => {
+                    let mut replies = replies.into_iter();
+                    let Some(first) = replies.next() else {
+                        continue;
+                    };
+                    for reply in replies {
+                        if let Some(id) = reply.get("id").and_then(Value::as_u64) {
+                            self.hold_reply(id, reply);
+                        }
+                    }
+                    first
+                }
+                resp => resp,
+            };
+            match resp.get("id").and_then(Value::as_u64) {
+                Some(id) if self.keepalive_ids.remove(&id) => continue,
+                _ => return Ok(resp),
+            }
+        }
+    }
+
+    /// Receives on the feed connection. If it has dropped and auto-reconnect is on,
+    /// reconnects, which resubscribes, before returning the error.
+    async fn receive_feed(&mut self) -> eyre::Result<String> {
+        match self.feed_conn.receive_text().await {
+            Ok(payload) => Ok(payload),
+            Err(err) if self.reconnect_policy.is_some() => {
+                self.reconnect().await?;
+                Err(err.wrap_err("feed connection dropped and was reconnected"))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Wraps `params` in a JSON-RPC envelope under the next request id.
+    fn build_request(&mut self, method: &str, params: Value) -> Value {
+        self.last_id += 1;
+        json!({
+            "id": self.last_id,
+            "jsonrpc": *self.jsonrpc_version,
+            "method": method,
+            "params": params
+        })
+    }
+
+    /// Sends a request and waits for the reply carrying its id, returning the
+    /// `result`. A JSON-RPC error reply is returned as an error.
+    async fn call(&mut self, method: &str, params: Value) -> eyre::Result<Value> {
+        let id = self.send_rpc(method, params).await?;
+        let mut resp = self.wait_for_reply(id).await?;
+        if let Some(error) = resp.get("error") {
+            return Err(eyre::Report::new(AoriError::from_rpc_error(error))
+                .wrap_err(format!("{} failed", method)));
+        }
+        Ok(resp["result"].take())
+    }
+
+    /// Waits for the reply to request `id`. Replies to other requests that arrive
+    /// first are held back for `wait_for_reply` or `recv_response` to pick up later.
+    pub async fn wait_for_reply(&mut self, id: u64) -> eyre::Result<Value> {
+        self.request_methods.remove(&id);
+        let timeout = self.request_timeout;
+        within(timeout, self.wait_for_reply_forever(id)).await
+    }
+
+    async fn wait_for_reply_forever(&mut self, id: u64) -> eyre::Result<Value> {
+        loop {
+            if let Some(resp) = self.pending_replies.remove(&id) {
+                return Ok(resp);
+            }
+            let resp = self.receive_request().await?;
+            match resp.get("id").and_then(Value::as_u64) {
+                Some(reply_id) if reply_id == id => return Ok(resp),
+                Some(reply_id) => self.hold_reply(reply_id, resp),
+                None => continue,
+            }
+        }
+    }
+
+    /// Holds `reply` for `wait_for_reply` or `recv_response` to pick up later. Only the
+    /// newest `MAX_PENDING_REPLIES` are kept, so replies nobody waits for, such as to
+    /// fire-and-forget `make_order`s, can't pile up in a long-running client.
+    fn hold_reply(&mut self, id: u64, reply: Value) {
+        self.pending_replies.insert(id, reply);
+        if self.pending_replies.len() > MAX_PENDING_REPLIES {
+            if let Some((dropped, _)) = self.pending_replies.pop_first() {
+                self.request_methods.remove(&dropped);
+            }
+        }
+    }
+
+    /// Remembers the method of request `id` for `recv_response`, keeping only the
+    /// newest `MAX_PENDING_REPLIES` like `hold_reply`.
+    fn track_method(&mut self, id: u64, method: &str) {
+        self.request_methods.insert(id, method.to_string());
+        if self.request_methods.len() > MAX_PENDING_REPLIES {
+            self.request_methods.pop_first();
+        }
+    }
+
+    /// Sends an `aori_ping` without waiting for the pong, which is dropped when it
+    /// arrives. Only the newest `MAX_PENDING_REPLIES` pings are remembered, like
+    /// `hold_reply`. Built from the backend's send future and a sleep, rather than as an
+    /// `async fn`, so it stays `Send`.
+    fn send_keepalive(&mut self) -> impl Future<Output = eyre::Result<()>> + Send + '_ {
+        let ping = self.build_request("aori_ping", json!([]));
+        self.keepalive_ids.insert(self.last_id);
+        if self.keepalive_ids.len() > MAX_PENDING_REPLIES {
+            self.keepalive_ids.pop_first();
+        }
+        let wait = self
+            .request_limiter
+            .as_mut()
+            .map_or(Duration::ZERO, RateLimiter::reserve);
+        let send = self.request_conn.send_text(ping.to_string());
+        async move {
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+            send.await
+        }
+    }
+
+    /// Sends a request without waiting for its reply and returns the request id, to
+    /// match against the `id` of a reply read later with `wait_for_reply`.
+    async fn send_rpc(&mut self, method: &str, params: Value) -> eyre::Result<u64> {
+        let req = self.build_request(method, params);
+        let id = self.last_id;
+        self.track_method(id, method);
+        self.send_request(req.to_string(), &[id]).await?;
+        Ok(id)
+    }
+
+    pub async fn ping(&mut self) -> eyre::Result<()> {
+        self.call("aori_ping", json!([])).await?;
+        Ok(())
+    }
+
+    /// Authenticates the wallet and returns the JWT the server issues for it.
+    pub async fn auth_wallet(&mut self) -> eyre::Result<String> {
+        let result = self
+            .call(
+                "aori_authWallet",
+                json!([{
+                    "address": *self.wallet_addr,
+                    "signature": *self.wallet_sig
+                }]),
+            )
+            .await?;
+        let jwt = result
+            .get("auth")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .ok_or_else(|| eyre::eyre!("auth response has no auth token: {}", result))?;
+        self.auth_expiry = jwt_expiry(&jwt).ok();
+        self.auth_token = Some(jwt.clone());
+        Ok(jwt)
+    }
+
+    /// The JWT issued by the last successful `auth_wallet`.
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// When the current JWT expires, if there is one and it carries an `exp` claim.
+    pub fn auth_expiry(&self) -> Option<SystemTime> {
+        self.auth_expiry
+    }
+
+    /// With `auto_reauth` on, re-authenticates if the JWT has expired.
+    async fn ensure_auth(&mut self) -> eyre::Result<()> {
+        if self.auto_reauth && self.is_auth_expired() {
+            let jwt = self.auth_wallet().await?;
+            self.check_auth(&jwt).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether the current JWT has passed its expiry. False when there is no JWT or
+    /// its expiry is unknown.
+    pub fn is_auth_expired(&self) -> bool {
+        self.auth_expiry
+            .is_some_and(|expiry| expiry <= SystemTime::now())
+    }
+
+    /// Reads the next reply on the request connection as JSON, oldest held-back reply
+    /// first.
+    async fn receive_json(&mut self) -> eyre::Result<Value> {
+        if let Some((_, resp)) = self.pending_replies.pop_first() {
+            return Ok(resp);
+        }
+        let timeout = self.request_timeout;
+        within(timeout, self.receive_request()).await
+    }
+
+    /// Receives the next reply on the request connection as a typed `AoriResponse`,
+    /// classified by the method of the request with the reply's id.
+    pub async fn recv_response(&mut self) -> eyre::Result<AoriResponse> {
+        let resp = self.receive_json().await?;
+        let method = resp
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| self.request_methods.remove(&id));
+        AoriResponse::from_json(method.as_deref(), &resp)
+    }
+
+    pub async fn check_auth(&mut self, jwt: &str) -> eyre::Result<()> {
+        self.call(
+            "aori_checkAuth",
+            json!([{
+                "auth": jwt
+            }]),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn view_orderbook(&mut self, base: &str, quote: &str) -> eyre::Result<u64> {
+        self.view_orderbook_filtered(base, quote, None, None, None)
+            .await
+    }
+
+    /// Like `view_orderbook`, asking for only one side of the book, at most `limit`
+    /// orders, or a price ordering. Filters left as `None` are not sent.
+    pub async fn view_orderbook_filtered(
+        &mut self,
+        base: &str,
+        quote: &str,
+        side: Option<Side>,
+        limit: Option<u32>,
+        sort: Option<SortOrder>,
+    ) -> eyre::Result<u64> {
+        self.send_rpc(
+            "aori_viewOrderbook",
+            json!([{
+                "chainId": self.chain_id,
+                "query": orderbook_query(base, quote, side, limit, sort)
+            }]),
+        )
+        .await
+    }
+
+    /// Reads the `base`/`quote` book, asks cheapest first and bids highest first. Each
+    /// side is sorted locally with `fill_rate_cmp`, whatever order the server lists it in.
+    pub async fn fetch_orderbook(&mut self, base: &str, quote: &str) -> eyre::Result<Orderbook> {
+        let asks_id = self
+            .view_orderbook_filtered(base, quote, Some(Side::Ask), None, None)
+            .await?;
+        let bids_id = self
+            .view_orderbook_filtered(base, quote, Some(Side::Bid), None, None)
+            .await?;
+        let mut asks = self.orderbook_reply(asks_id).await?;
+        let mut bids = self.orderbook_reply(bids_id).await?;
+        for side in [&mut asks, &mut bids] {
+            side.sort_by(|a, b| fill_rate_cmp(&b.order, &a.order));
+        }
+        Ok(Orderbook { bids, asks })
+    }
+
+    async fn orderbook_reply(&mut self, id: u64) -> eyre::Result<Vec<RestingOrder>> {
+        let resp = self.wait_for_reply(id).await?;
+        if let Some(error) = resp.get("error") {
+            return Err(eyre::Report::new(AoriError::from_rpc_error(error))
+                .wrap_err("aori_viewOrderbook failed"));
+        }
+        RestingOrder::from_orderbook_result(&resp["result"])
+    }
+
+    /// Takes the best-priced order on `side` of the `base`/`quote` book whose first
+    /// offer item is at most `max_amount`, signing the counter order with the wallet's
+    /// Seaport counter. Prices are compared locally (see `fill_rate_cmp`) rather than
+    /// trusting the order the server lists them in. Returns the `aori_takeOrder`
+    /// request id.
+    pub async fn take_best(
+        &mut self,
+        base: &str,
+        quote: &str,
+        side: Side,
+        max_amount: U256,
+    ) -> eyre::Result<u64> {
+        let id = self
+            .view_orderbook_filtered(base, quote, Some(side), None, None)
+            .await?;
+        let best = self
+            .orderbook_reply(id)
+            .await?
+            .into_iter()
+            .filter(|resting| {
+                !resting.order.consideration.is_empty()
+                    && resting
+                        .order
+                        .offer
+                        .first()
+                        .is_some_and(|item| item.startAmount <= max_amount)
+            })
+            // `min_by` with the comparison reversed keeps the first of equally good orders.
+            .min_by(|a, b| fill_rate_cmp(&b.order, &a.order))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "no {:?} order on {}/{} offers at most {}",
+                    side,
+                    base,
+                    quote,
+                    max_amount
+                )
+            })?;
+        let counter = match self.counter {
+            Some(counter) => counter,
+            None => self.fetch_counter().await?,
+        };
+        let mut taking = counter_order(&best.order, Address::from(self.wallet.address().0));
+        taking.counter = counter;
+        self.take_order(taking, &best.order_hash, None).await
+    }
+
+    /// Whether the `base`/`quote` market is accepting orders, so makers can avoid
+    /// posting into a halted market.
+    pub async fn market_status(&mut self, base: &str, quote: &str) -> eyre::Result<MarketStatus> {
+        let result = self
+            .call(
+                "aori_marketStatus",
+                json!([{
+                    "chainId": self.chain_id,
+                    "base": base,
+                    "quote": quote
+                }]),
+            )
+            .await?;
+        let status = result
+            .get("status")
+            .ok_or_else(|| eyre::eyre!("market status response has no status: {}", result))?;
+        Ok(MarketStatus::deserialize(status)?)
+    }
+
+    pub async fn make_order(&mut self, order_params: OrderComponents) -> eyre::Result<u64> {
+        self.send_order(order_params, OrderVisibility::Public, None)
+            .await
+    }
+
+    pub async fn make_order_with_visibility(
+        &mut self,
+        order_params: OrderComponents,
+        visibility: OrderVisibility,
+    ) -> eyre::Result<u64> {
+        self.send_order(order_params, visibility, None).await
+    }
+
+    /// Posts an order that stays off the public orderbook and whose offered items can
+    /// only go to `taker`: it is restricted with `builder::private_to` before signing,
+    /// so Seaport enforces the restriction on-chain.
+    pub async fn make_private_order(
+        &mut self,
+        order_params: OrderComponents,
+        taker: Address,
+    ) -> eyre::Result<u64> {
+        self.send_order(
+            private_to(order_params, taker),
+            OrderVisibility::Private,
+            None,
+        )
+        .await
+    }
+
+    /// Splits `order_params` into `n` child orders (see `OrderComponents::split`), posts
+    /// them as one batch with `make_orders` and returns their order hashes. Every child
+    /// is checked and signed before any is sent, so a failure posts none of them.
+    pub async fn make_split(
+        &mut self,
+        order_params: OrderComponents,
+        n: usize,
+    ) -> eyre::Result<Vec<H256>> {
+        let children = order_params.split(n, &mut RandomSalt)?;
+        let hashes = children
+            .iter()
+            .map(|child| {
+                H256::from_slice(child.eip712_signing_hash(&self.seaport_domain).as_slice())
+            })
+            .collect();
+        self.make_orders(children).await?;
+        Ok(hashes)
+    }
+
+    /// Signs an EIP-2612 permit for `token_domain` (see `permit::permit_domain`). The
+    /// spender should be Seaport, or the conduit when the order uses a conduit key.
+    pub async fn sign_permit(
+        &self,
+        permit: &Permit,
+        token_domain: &Eip712Domain,
+    ) -> eyre::Result<Signature> {
+        let typed = TypedData::new(permit, token_domain);
+        Ok(self.wallet.sign_typed_data(&typed).await?)
+    }
+
+    /// Posts an order together with a signed permit for its offered token, so the
+    /// allowance can be set at fill time instead of by a separate approval.
+    pub async fn make_order_with_permit(
+        &mut self,
+        order_params: OrderComponents,
+        permit: Permit,
+        permit_sig: Signature,
+    ) -> eyre::Result<u64> {
+        let mut permit_json = permit.to_json();
+        permit_json["signature"] = format!("0x{}", permit_sig).into();
+        self.send_order(order_params, OrderVisibility::Public, Some(permit_json))
+            .await
+    }
+
+    async fn send_order(
+        &mut self,
+        order_params: OrderComponents,
+        visibility: OrderVisibility,
+        permit: Option<Value>,
+    ) -> eyre::Result<u64> {
+        self.check_order(&order_params)?;
+        self.ensure_auth().await?;
+        let params = self
+            .make_order_params(&order_params, visibility, permit)
+            .await?;
+        let order = self.build_request("aori_makeOrder", json!([params]));
+        let id = self.last_id;
+        self.track_method(id, "aori_makeOrder");
+        self.send_request(order.to_string(), &[id]).await?;
+        Ok(id)
+    }
+
+    /// Posts public orders as one JSON-RPC batch frame instead of a frame per order,
+    /// returning their request ids in the same order. Every order is checked before
+    /// anything is signed or sent.
+    pub async fn make_orders(&mut self, orders: Vec<OrderComponents>) -> eyre::Result<Vec<u64>> {
+        if orders.is_empty() {
+            return Ok(Vec::new());
+        }
+        for order_params in &orders {
+            self.check_order(order_params)?;
+        }
+        self.ensure_auth().await?;
+        let mut batch = Vec::with_capacity(orders.len());
+        let mut ids = Vec::with_capacity(orders.len());
+        for order_params in &orders {
+            let params = self
+                .make_order_params(order_params, OrderVisibility::Public, None)
+                .await?;
+            batch.push(self.build_request("aori_makeOrder", json!([params])));
+            ids.push(self.last_id);
+            self.track_method(self.last_id, "aori_makeOrder");
+        }
+        self.send_request(Value::Array(batch).to_string(), &ids)
+            .await?;
+        Ok(ids)
+    }
+
+    /// Local checks every order must pass before it is signed.
+    fn check_order(&self, order_params: &OrderComponents) -> eyre::Result<()> {
+        order_params.validate().map_err(AoriError::Validation)?;
+        check_order_timestamps(order_params)
+            .map_err(|err| AoriError::InvalidOrder(err.to_string()))?;
+        if let Some(max_lifetime) = self.max_order_lifetime {
+            check_order_lifetime(order_params, max_lifetime)
+                .map_err(|err| AoriError::InvalidOrder(err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Signs `order_params` and builds the `aori_makeOrder` params for it.
+    async fn make_order_params(
+        &self,
+        order_params: &OrderComponents,
+        visibility: OrderVisibility,
+        permit: Option<Value>,
+    ) -> eyre::Result<Value> {
+        let signed_sig = self.sign_order(order_params).await?;
+        let mut params = json!({
+            "order": {
+                "signature": format!("0x{}", signed_sig),
+                "parameters": order_params.to_json()
+            },
+            "chainId": self.chain_id
+        });
+        visibility.apply(&mut params);
+        if let Some(permit) = permit {
+            params["permit"] = permit;
+        }
+        Ok(params)
+    }
+
+    /// Signs the EIP-712 hash of `order_params` under this provider's Seaport domain,
+    /// reporting it to the audit hook if one is set.
+    async fn sign_order(&self, order_params: &OrderComponents) -> eyre::Result<Signature> {
+        let typed = TypedData::new(order_params, &self.seaport_domain);
+        let signed_sig: Signature = self.wallet.sign_typed_data(&typed).await?;
+        if let Some(audit_hook) = &self.audit_hook {
+            audit_hook(&SigningRecord::new(
+                order_params,
+                &self.seaport_domain,
+                signed_sig,
+            ));
+        }
+        Ok(signed_sig)
+    }
+
+    /// Takes the resting order `order_hash`, signing its components the same way
+    /// `make_order` does. `seat_id` is only sent when set.
+    pub async fn take_order(
+        &mut self,
+        order_params: OrderComponents,
+        order_hash: &str,
+        seat_id: Option<u64>,
+    ) -> eyre::Result<u64> {
+        self.ensure_auth().await?;
+        let signed_sig = self.sign_order(&order_params).await?;
+        let mut params = json!({
+            "order": {
+                "signature": format!("0x{}", signed_sig),
+                "parameters": order_params.to_json()
+            },
+            "orderHash": order_hash,
+            "chainId": self.chain_id
+        });
+        if let Some(seat_id) = seat_id {
+            params["seatId"] = seat_id.into();
+        }
+        self.send_rpc("aori_takeOrder", json!([params])).await
+    }
+
+    /// Cancels a resting order by signing its hash. `order_hash` may be passed with or
+    /// without the `0x` prefix. Fails with `AoriError::RawDigestUnsupported` for signers
+    /// that can't sign a bare hash, such as hardware wallets.
+    pub async fn cancel_order(&mut self, order_hash: &str) -> eyre::Result<u64> {
+        self.ensure_auth().await?;
+        let (hash, sig) = self.sign_order_hash(order_hash).await?;
+        self.send_rpc(
+            "aori_cancelOrder",
+            json!([{
+                "orderId": format!("{:?}", hash),
+                "signature": format!("0x{}", sig)
+            }]),
+        )
+        .await
+    }
+
+    /// Reports that the counterparty to `order_hash` didn't settle, so the relayer can
+    /// track its reputation. Signed over the order hash like `cancel_order`.
+    pub async fn fail_order(&mut self, order_hash: &str) -> eyre::Result<u64> {
+        self.ensure_auth().await?;
+        let (hash, sig) = self.sign_order_hash(order_hash).await?;
+        self.send_rpc(
+            "aori_failOrder",
+            json!([{
+                "orderHash": format!("{:?}", hash),
+                "signature": format!("0x{}", sig)
+            }]),
+        )
+        .await
+    }
+
+    /// Parses `order_hash`, with or without the `0x` prefix, and signs it as-is.
+    async fn sign_order_hash(&self, order_hash: &str) -> eyre::Result<(H256, Signature)> {
+        let hash = order_hash.strip_prefix("0x").unwrap_or(order_hash);
+        let hash: H256 = hash.parse().context("order hash is not a valid bytes32")?;
+        let sig: Signature = self
+            .wallet
+            .sign_typed_data(&RawDigest(hash))
+            .await
+            .map_err(|err| AoriError::RawDigestUnsupported(err.to_string()))?;
+        Ok((hash, sig))
+    }
+
+    /// Cancels every open order for the wallet. The server checks the signature against
+    /// the wallet address string, the same message `auth_wallet` proves, so the
+    /// signature made at construction (`wallet_sig`) is reused rather than re-signing.
+    pub async fn cancel_all_orders(&mut self) -> eyre::Result<u64> {
+        self.ensure_auth().await?;
+        self.send_rpc(
+            "aori_cancelAllOrders",
+            json!([{
+                "address": *self.wallet_addr,
+                "signature": *self.wallet_sig
+            }]),
+        )
+        .await
+    }
+
+    /// Lists the wallet's open orders, e.g. to reconcile local state after a disconnect.
+    pub async fn account_orders(&mut self) -> eyre::Result<u64> {
+        self.ensure_auth().await?;
+        self.send_rpc(
+            "aori_accountOrders",
+            json!([{
+                "offerer": *self.wallet_addr,
+                "signature": *self.wallet_sig
+            }]),
+        )
+        .await
+    }
+
+    pub async fn order_status(&mut self, order_hash: &str) -> eyre::Result<u64> {
+        self.send_rpc(
+            "aori_orderStatus",
+            json!([{
+                "orderHash": order_hash
+            }]),
+        )
+        .await
+    }
+
+    /// Asks for an RFQ quote selling `input_amount` of `input_token` for `output_token`.
+    pub async fn request_quote(
+        &mut self,
+        input_token: &str,
+        output_token: &str,
+        input_amount: U256,
+    ) -> eyre::Result<u64> {
+        self.send_rpc(
+            "aori_requestQuote",
+            json!([{
+                "chainId": self.chain_id,
+                "inputToken": input_token,
+                "outputToken": output_token,
+                "inputAmount": format!("{}", input_amount)
+            }]),
+        )
+        .await
+    }
+
+    /// Asks for the wallet's current Seaport counter, which every order must be signed
+    /// with.
+    pub async fn get_counter(&mut self) -> eyre::Result<u64> {
+        self.send_rpc(
+            "aori_getCounter",
+            json!([{
+                "address": *self.wallet_addr,
+                "chainId": self.chain_id
+            }]),
+        )
+        .await
+    }
+
+    /// Like `get_counter`, but waits for the reply and caches the counter in
+    /// `self.counter`.
+    pub async fn fetch_counter(&mut self) -> eyre::Result<U256> {
+        let id = self.get_counter().await?;
+        let resp = self.wait_for_reply(id).await?;
+        let counter = match resp.pointer("/result/counter") {
+            Some(Value::String(counter)) => counter.parse::<U256>()?,
+            Some(Value::Number(counter)) => U256::from(
+                counter
+                    .as_u64()
+                    .ok_or_else(|| eyre::eyre!("invalid counter: {}", counter))?,
+            ),
+            _ => eyre::bail!("counter response has no counter: {}", resp),
+        };
+        self.counter = Some(counter);
+        Ok(counter)
+    }
+
+    /// Waits for the reply to the `aori_makeOrder` request `id` that posted `order` and
+    /// errors unless the `orderHash` the relayer echoes is the hash this provider signed
+    /// under its Seaport domain. A mismatch means the two disagree on the domain, which
+    /// otherwise only shows up as signature rejections.
+    pub async fn verify_domain(&mut self, order: &OrderComponents, id: u64) -> eyre::Result<()> {
+        let resp = self.wait_for_reply(id).await?;
+        if let Some(error) = resp.get("error") {
+            return Err(eyre::Report::new(AoriError::from_rpc_error(error))
+                .wrap_err("aori_makeOrder failed"));
+        }
+        let relayer_hash: FixedBytes<32> = resp
+            .pointer("/result/orderHash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre::eyre!("aori_makeOrder reply has no orderHash: {}", resp))?
+            .parse()
+            .context("relayer order hash is not a valid bytes32")?;
+        verify_order_hash(order, &self.seaport_domain, relayer_hash)
+    }
+
+    /// Signs orders for a Seaport deployment at `verifying_contract` on this provider's
+    /// chain instead of the canonical deployment.
+    pub fn set_verifying_contract(&mut self, verifying_contract: Address) -> eyre::Result<()> {
+        self.seaport_domain = seaport_domain_for(self.chain_id, verifying_contract)?;
+        Ok(())
+    }
+
+    /// Subscribes to the orderbook feed. Clients that already hold book state can pass
+    /// `with_snapshot: false` to skip the initial full snapshot.
+    pub async fn subscribe_orderbook(&mut self, with_snapshot: bool) -> eyre::Result<u64> {
+        self.send_subscription(subscribe_orderbook_params(with_snapshot))
+            .await
+    }
+
+    /// Subscribes to orderbook events for only the given base token, quote token and
+    /// chain. Filters left as `None` are not sent.
+    pub async fn subscribe_orderbook_filtered(
+        &mut self,
+        base: Option<&str>,
+        quote: Option<&str>,
+        chain_id: Option<u64>,
+    ) -> eyre::Result<u64> {
+        self.send_subscription(filtered_subscribe_params(base, quote, chain_id))
+            .await
+    }
+
+    async fn send_subscription(&mut self, params: Value) -> eyre::Result<u64> {
+        let sub_req = self.build_request("aori_subscribeOrderbook", params.clone());
+        self.orderbook_subscription = Some(params);
+        self.send_feed(sub_req).await
+    }
+
+    /// Subscribes to events for only the orders this wallet made or took, rather than
+    /// the whole book. Events arrive on the feed connection like orderbook events and
+    /// are read with `next_orderbook_event`.
+    pub async fn subscribe_account(&mut self) -> eyre::Result<u64> {
+        let sub_req = self.build_request("aori_subscribeAccount", self.account_params());
+        self.account_subscribed = true;
+        self.send_feed(sub_req).await
+    }
+
+    fn account_params(&self) -> Value {
+        json!([{
+            "offerer": *self.wallet_addr,
+            "signature": *self.wallet_sig
+        }])
+    }
+
+    /// Sends a subscription on the feed connection and returns its request id.
+    async fn send_feed(&mut self, sub_req: Value) -> eyre::Result<u64> {
+        let id = self.last_id;
+        if let Err(err) = self.send_feed_text(sub_req.to_string()).await {
+            if self.reconnect_policy.is_none() {
+                return Err(err);
+            }
+            // Reconnecting replays the subscription.
+            self.reconnect()
+                .await
+                .wrap_err(format!("feed connection dropped: {}", err))?;
+        }
+        Ok(id)
+    }
+
+    /// Waits for the next orderbook event on the feed connection, skipping frames that
+    /// aren't events.
+    pub async fn next_orderbook_event(&mut self) -> eyre::Result<OrderbookEvent> {
+        loop {
+            let frame: Value = serde_json::from_str(&self.receive_feed().await?)?;
+            if let Some(event) = OrderbookEvent::from_json(&frame)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// `next_orderbook_event` as a stream. Errors are yielded in place; without
+    /// auto-reconnect the stream ends after the first one.
+    pub fn orderbook_stream(&mut self) -> impl Stream<Item = eyre::Result<OrderbookEvent>> + '_ {
+        stream::unfold(Some(self), |apv| async move {
+            let apv = apv?;
+            let event = apv.next_orderbook_event().await;
+            let keep_going = event.is_ok() || apv.reconnect_policy.is_some();
+            Some((event, keep_going.then_some(apv)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{MockAoriServer, MOCK_JWT};
+    use alloy_primitives::{address, Address, U256};
+    use aori_types::builder::erc20_swap_order;
+    use aori_types::constants::{DEFAULT_CONDUIT_KEY, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH};
+    use aori_types::seaport::{
+        order_hash, ConsiderationItem, ItemType, OfferItem, OrderComponents, OrderType,
+    };
+    use aori_types::time::{secs_from_now, Timestamp};
+    use aori_types::validate::OrderValidationError;
+    use futures::StreamExt;
+    use std::{
+        cell::{Cell, RefCell},
+        collections::VecDeque,
+    };
+
+    /// In-memory backend that records what the provider sends and replays queued
+    /// replies.
+    #[derive(Default)]
+    struct RecordingWs {
+        sent: Vec<String>,
+        replies: VecDeque<String>,
+        /// Fails every send, as if the server had dropped the connection.
+        closed: bool,
+    }
+
+    thread_local! {
+        /// Replies queued on the next `RecordingWs` that `connect` opens.
+        static CONNECT_REPLIES: RefCell<VecDeque<String>> = RefCell::default();
+        /// Whether `connect` opens connections that fail every send.
+        static CONNECT_CLOSED: Cell<bool> = const { Cell::new(false) };
+        /// How many connections `connect` has opened.
+        static CONNECT_COUNT: Cell<u32> = const { Cell::new(0) };
+    }
+
+    impl WsBackend for RecordingWs {
+        async fn connect(_url: &str) -> eyre::Result<Self> {
+            CONNECT_COUNT.with(|count| count.set(count.get() + 1));
+            Ok(Self {
+                replies: CONNECT_REPLIES.with(|replies| replies.take()),
+                closed: CONNECT_CLOSED.with(Cell::get),
+                ..Self::default()
+            })
+        }
+
+        async fn send_text(&mut self, payload: String) -> eyre::Result<()> {
+            if self.closed {
+                eyre::bail!("websocket closed");
+            }
+            self.sent.push(payload);
+            Ok(())
+        }
+
+        async fn receive_text(&mut self) -> eyre::Result<String> {
+            self.replies
+                .pop_front()
+                .ok_or_else(|| eyre::eyre!("no reply queued"))
+        }
+
+        async fn close(&mut self) -> eyre::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn test_wallet() -> LocalWallet {
+        "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+    }
+
+    async fn recording_provider() -> AoriProvider<RecordingWs> {
+        AoriProvider::from_connections(
+            RecordingWs::default(),
+            RecordingWs::default(),
+            test_wallet(),
+            5,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn last_request(apv: &AoriProvider<RecordingWs>) -> Value {
+        serde_json::from_str(apv.request_conn.sent.last().unwrap()).unwrap()
+    }
+
+    fn sample_order() -> OrderComponents {
+        erc20_swap_order(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000000000000000_u128),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500000),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        )
+    }
+
+    #[tokio::test]
+    async fn builder_derives_address() {
+        let apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(1)
+            .request_url("wss://relayer.example")
+            .build_with_backend::<RecordingWs>()
+            .await
+            .unwrap();
+        assert_eq!(
+            *apv.wallet_addr,
+            ethers::utils::to_checksum(&test_wallet().address(), None)
+        );
+        assert_eq!(apv.chain_id, 1);
+        assert_eq!(
+            apv.seaport_domain.separator(),
+            seaport_domain(1).separator()
+        );
+        assert_eq!(&*apv.request_url, "wss://relayer.example");
+        assert_eq!(&*apv.feed_url, MARKET_FEED_URL);
+
+        assert!(AoriProviderBuilder::new()
+            .chain_id(1)
+            .build_with_backend::<RecordingWs>()
+            .await
+            .is_err());
+        assert!(AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .build_with_backend::<RecordingWs>()
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn wallet_address_must_match_key() {
+        let wallet = test_wallet();
+        let address = format!("{:?}", wallet.address());
+        assert!(check_wallet_address(&wallet, &address).is_ok());
+        assert!(check_wallet_address(&wallet, &address.to_uppercase().replace("0X", "0x")).is_ok());
+        assert!(
+            check_wallet_address(&wallet, "0x1111111111111111111111111111111111111111").is_err()
+        );
+        assert!(check_wallet_address(&wallet, "not an address").is_err());
+    }
+
+    #[tokio::test]
+    async fn private_order_params() {
+        let mut apv = recording_provider().await;
+        let taker = address!("D3664B5e72B46eaba722aB6f43c22dBF40181954");
+        let order = sample_order();
+        apv.make_private_order(order.clone(), taker).await.unwrap();
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_makeOrder");
+        assert_eq!(req["params"][0]["isPublic"], false);
+        assert!(req["params"][0].get("taker").is_none());
+        assert_eq!(
+            req["params"][0]["order"]["parameters"],
+            private_to(order, taker).to_json()
+        );
+    }
+
+    #[tokio::test]
+    async fn tracks_jwt_expiry() {
+        let jwt = |exp: u64| {
+            format!(
+                "{}.{}.c2ln",
+                URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#),
+                URL_SAFE_NO_PAD.encode(json!({ "sub": "0xabc", "exp": exp }).to_string())
+            )
+        };
+        let far_future = Timestamp::now().as_secs() + 3600;
+        let mut apv = recording_provider().await;
+        for (id, exp) in [(1, 1697240202), (2, far_future)] {
+            apv.request_conn
+                .replies
+                .push_back(json!({ "id": id, "result": { "auth": jwt(exp) } }).to_string());
+        }
+        assert!(!apv.is_auth_expired());
+
+        apv.auth_wallet().await.unwrap();
+        assert_eq!(
+            apv.auth_expiry(),
+            Some(UNIX_EPOCH + Duration::from_secs(1697240202))
+        );
+        assert!(apv.is_auth_expired());
+
+        apv.auth_wallet().await.unwrap();
+        assert_eq!(apv.auth_token(), Some(jwt(far_future).as_str()));
+        assert!(!apv.is_auth_expired());
+        assert!(jwt_expiry("not-a-jwt").is_err());
+    }
+
+    #[tokio::test]
+    async fn reauths_when_expired() {
+        let mut apv = recording_provider().await;
+        apv.auto_reauth = true;
+        apv.cancel_all_orders().await.unwrap();
+        assert_eq!(apv.request_conn.sent.len(), 1);
+
+        apv.auth_expiry = Some(UNIX_EPOCH + Duration::from_secs(1697240202));
+        for reply in [
+            json!({ "id": 2, "result": { "auth": "eyJhbGciOi" } }),
+            json!({ "id": 3, "result": { "valid": true } }),
+        ] {
+            apv.request_conn.replies.push_back(reply.to_string());
+        }
+        apv.cancel_all_orders().await.unwrap();
+
+        let methods: Vec<Value> = apv.request_conn.sent[1..]
+            .iter()
+            .map(|payload| serde_json::from_str::<Value>(payload).unwrap()["method"].take())
+            .collect();
+        assert_eq!(
+            methods,
+            ["aori_authWallet", "aori_checkAuth", "aori_cancelAllOrders"]
+        );
+        assert_eq!(apv.auth_token(), Some("eyJhbGciOi"));
+        assert!(!apv.is_auth_expired());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_order_locally() {
+        let mut apv = recording_provider().await;
+        let mut order = sample_order();
+        order.offer.clear();
+
+        let err = apv.make_order(order).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::Validation(OrderValidationError::EmptyOffer))
+        ));
+        assert!(apv.request_conn.sent.is_empty());
+    }
+
+    #[tokio::test]
+    async fn make_split_posts_one_batch() {
+        let mut apv = recording_provider().await;
+        let hashes = apv.make_split(sample_order(), 3).await.unwrap();
+
+        assert_eq!(hashes.len(), 3);
+        assert!(hashes[0] != hashes[1] && hashes[1] != hashes[2]);
+        assert_eq!(apv.request_conn.sent.len(), 1);
+        let batch = last_request(&apv);
+        assert_eq!(batch.as_array().unwrap().len(), 3);
+        assert!(batch
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|req| req["method"] == "aori_makeOrder"));
+
+        // A child that fails its checks stops the whole split before anything is sent.
+        let mut apv = recording_provider()
+            .await
+            .with_max_order_lifetime(Duration::from_secs(60));
+        assert!(apv.make_split(sample_order(), 3).await.is_err());
+        assert!(apv.request_conn.sent.is_empty());
+    }
+
+    #[tokio::test]
+    async fn make_orders_batches() {
+        let mut apv = recording_provider().await;
+        let mut second = sample_order();
+        second.salt = U256::from(2);
+        let ids = apv.make_orders(vec![sample_order(), second]).await.unwrap();
+
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(apv.request_conn.sent.len(), 1);
+        let batch = last_request(&apv);
+        assert_eq!(batch[0]["id"], 1);
+        assert_eq!(batch[1]["id"], 2);
+        assert_eq!(batch[1]["method"], "aori_makeOrder");
+        assert_eq!(batch[1]["params"][0]["order"]["parameters"]["salt"], "2");
+
+        // The batch reply is split back into replies by id.
+        apv.request_conn.replies.push_back(
+            json!([
+                { "id": 1, "result": { "orderHash": "0x01" } },
+                { "id": 2, "result": { "orderHash": "0x02" } }
+            ])
+            .to_string(),
+        );
+        assert_eq!(
+            apv.wait_for_reply(2).await.unwrap()["result"]["orderHash"],
+            "0x02"
+        );
+        assert_eq!(
+            apv.wait_for_reply(1).await.unwrap()["result"]["orderHash"],
+            "0x01"
+        );
+    }
 
-use eyre::Context;
+    #[tokio::test]
+    async fn fetch_orderbook_sides() {
+        let mut apv = recording_provider().await;
+        let mut cheap = sample_order();
+        cheap.consideration[0].startAmount = U256::from(1400000);
+        let resting = |hash: &str, order: &OrderComponents| {
+            json!({
+                "orderHash": hash,
+                "order": { "parameters": order.to_json(), "signature": "0xabcd" }
+            })
+        };
+        // Replies out of order still land on the right side, and each side comes back
+        // best price first whatever order it was listed in.
+        for reply in [
+            json!({ "id": 2, "result": { "orders": [resting("0x02", &sample_order())] } }),
+            json!({ "id": 1, "result": { "orders": [
+                resting("0x01", &sample_order()),
+                resting("0x03", &cheap)
+            ] } }),
+        ] {
+            apv.request_conn.replies.push_back(reply.to_string());
+        }
 
-use ethers::{
-    prelude::{k256::ecdsa::SigningKey, LocalWallet, Wallet, Ws},
-    providers::{Middleware, Provider},
-    signers::Signer,
-    types::{Signature, H256},
-};
+        let book = apv.fetch_orderbook("WETH", "USDC").await.unwrap();
+        let hashes = |side: &[RestingOrder]| {
+            side.iter()
+                .map(|o| o.order_hash.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(hashes(&book.asks), ["0x03", "0x01"]);
+        assert_eq!(hashes(&book.bids), ["0x02"]);
 
-use alloy_sol_types::SolStruct;
+        let asks_req: Value = serde_json::from_str(&apv.request_conn.sent[0]).unwrap();
+        assert_eq!(asks_req["params"][0]["query"]["side"], "ask");
+        assert_eq!(last_request(&apv)["params"][0]["query"]["side"], "bid");
+    }
 
-use alloy_primitives::FixedBytes;
+    #[tokio::test]
+    async fn take_best_picks_best_price() {
+        let mut apv = recording_provider().await;
+        // Offers 1 WETH for 1500 USDC, a far better rate than the others but too big.
+        let mut big = sample_order();
+        big.offer[0].startAmount = U256::from(10).pow(U256::from(18));
+        // Offers 0.001 WETH for 1.4 USDC, cheaper than sample_order's 1.5 USDC.
+        let mut cheap = sample_order();
+        cheap.consideration[0].startAmount = U256::from(1400000);
+        let resting = |hash: &str, order: &OrderComponents| {
+            json!({
+                "orderHash": hash,
+                "order": { "parameters": order.to_json(), "signature": "0xabcd" }
+            })
+        };
+        // The best fillable order is listed last.
+        for reply in [
+            json!({ "id": 1, "result": { "orders": [
+                resting("0x01", &big),
+                resting("0x02", &sample_order()),
+                resting("0x03", &cheap)
+            ] } }),
+            json!({ "id": 2, "result": { "counter": "4" } }),
+        ] {
+            apv.request_conn.replies.push_back(reply.to_string());
+        }
 
-use aori_types::{
-    constants::{MARKET_FEED_URL, REQUEST_URL},
-    seaport::{OrderComponents, SEAPORT_DOMAIN},
-};
+        let id = apv
+            .take_best(
+                "WETH",
+                "USDC",
+                Side::Ask,
+                U256::from(10).pow(U256::from(16)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(id, 3);
+        let query: Value = serde_json::from_str(&apv.request_conn.sent[0]).unwrap();
+        assert!(query["params"][0]["query"].get("sort").is_none());
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_takeOrder");
+        assert_eq!(req["params"][0]["orderHash"], "0x03");
+        let taking = &req["params"][0]["order"]["parameters"];
+        assert_eq!(taking["offerer"], *apv.wallet_addr);
+        assert_eq!(taking["counter"], "4");
 
-pub struct AoriProvider {
-    pub request_conn: WebSocket,
-    pub feed_conn: WebSocket,
-    pub wallet: Wallet<SigningKey>,
-    pub chain_id: u64,
-    pub last_id: u64,
-    pub wallet_addr: Arc<str>,
-    pub wallet_sig: Arc<str>,
-}
+        apv.request_conn
+            .replies
+            .push_back(json!({ "id": 4, "result": { "orders": [] } }).to_string());
+        assert!(apv
+            .take_best("WETH", "USDC", Side::Bid, U256::MAX)
+            .await
+            .is_err());
+    }
 
-impl AoriProvider {
-    pub async fn new_from_env() -> eyre::Result<Self> {
-        let key = std::env::var("PRIVATE_KEY").context("missing PRIVATE_KEY")?;
-        let address = std::env::var("WALLET_ADDRESS").context("missing WALLET_ADDRESS")?;
-        let node = std::env::var("NODE_URL").context("missing NODE_URL")?;
+    #[test]
+    fn fill_rate_counts_same_token_fees() {
+        let mut with_fee = sample_order();
+        let mut fee = with_fee.consideration[0].clone();
+        fee.startAmount = U256::from(200000);
+        fee.endAmount = U256::from(200000);
+        with_fee.consideration.push(fee);
+        assert_eq!(
+            fill_amounts(&with_fee),
+            (U256::from(1000000000000000_u128), U256::from(1700000))
+        );
+        assert_eq!(fill_rate_cmp(&sample_order(), &with_fee), Ordering::Greater);
+        assert_eq!(
+            fill_rate_cmp(&sample_order(), &sample_order()),
+            Ordering::Equal
+        );
+    }
 
-        let pv = Provider::<Ws>::connect(&node).await?;
-        let chain_id = pv.get_chainid().await?.low_u64();
+    #[tokio::test]
+    async fn verify_domain_against_relayer() {
+        let mut apv = recording_provider().await;
+        let order = sample_order();
+        // The relayer echoes the hash under the canonical domain.
+        let relayer_hash = format!("{}", order_hash(&order, 5));
+        for id in [1, 2] {
+            apv.request_conn.replies.push_back(
+                json!({ "id": id, "result": { "orderHash": relayer_hash } }).to_string(),
+            );
+        }
 
-        let wallet = key.parse::<LocalWallet>()?.with_chain_id(chain_id);
-        let sig: Signature = wallet.sign_message(address.as_str()).await?;
-        let request_conn = WebSocket::connect(REQUEST_URL).await?;
-        let feed_conn = WebSocket::connect(MARKET_FEED_URL).await?;
+        let id = apv.make_order(order.clone()).await.unwrap();
+        apv.verify_domain(&order, id).await.unwrap();
 
-        Ok(Self {
-            request_conn,
-            feed_conn,
-            wallet,
-            chain_id,
-            last_id: 0,
-            wallet_addr: address.into(),
-            wallet_sig: format!("0x{}", sig).into(),
-        })
+        apv.set_verifying_contract(address!("1111111111111111111111111111111111111111"))
+            .unwrap();
+        let id = apv.make_order(order.clone()).await.unwrap();
+        let err = apv.verify_domain(&order, id).await.unwrap_err();
+        assert!(err.to_string().contains("domain mismatch"));
+
+        apv.request_conn.replies.push_back(
+            json!({ "id": 3, "error": { "code": -32000, "message": "invalid signature" } })
+                .to_string(),
+        );
+        let id = apv.make_order(order.clone()).await.unwrap();
+        let err = apv.verify_domain(&order, id).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::Rpc { .. })
+        ));
     }
-    pub async fn ping(&mut self) -> eyre::Result<()> {
-        self.last_id += 1;
-        let ping = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_ping",
-            "params": []
-        });
-        self.request_conn.send_text(ping.to_string()).await?;
-        Ok(())
+
+    #[tokio::test]
+    async fn take_order_params() {
+        let mut apv = recording_provider().await;
+        let order_hash = "0x7f0c6bbf6a6e3c5e16f2b2ea93c3f4a4cc95b4dbb2a1d4b0f0a2b5a6d6e0c1f3";
+
+        apv.take_order(sample_order(), order_hash, None)
+            .await
+            .unwrap();
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_takeOrder");
+        assert_eq!(req["params"][0]["orderHash"], order_hash);
+        assert_eq!(req["params"][0]["chainId"], 5);
+        assert!(req["params"][0]["order"]["signature"].is_string());
+        assert!(req["params"][0].get("seatId").is_none());
+
+        apv.take_order(sample_order(), order_hash, Some(3))
+            .await
+            .unwrap();
+        assert_eq!(last_request(&apv)["params"][0]["seatId"], 3);
     }
 
-    pub async fn auth_wallet(&mut self) -> eyre::Result<()> {
-        self.last_id += 1;
-        let auth = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_authWallet",
-            "params": [{
-                "address": *self.wallet_addr,
-                "signature": *self.wallet_sig
-            }]
-        });
-        self.request_conn.send_text(auth.to_string()).await?;
-        Ok(())
+    #[tokio::test]
+    async fn cancel_order_signs_hash() {
+        let mut apv = recording_provider().await;
+        let order_hash = "7f0c6bbf6a6e3c5e16f2b2ea93c3f4a4cc95b4dbb2a1d4b0f0a2b5a6d6e0c1f3";
+
+        apv.cancel_order(order_hash).await.unwrap();
+        let bare = last_request(&apv);
+        apv.cancel_order(&format!("0x{order_hash}")).await.unwrap();
+        let prefixed = last_request(&apv);
+
+        assert_eq!(bare["method"], "aori_cancelOrder");
+        assert_eq!(bare["params"], prefixed["params"]);
+        assert_eq!(bare["params"][0]["orderId"], format!("0x{order_hash}"));
+        let sig: Signature = bare["params"][0]["signature"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            sig.recover(order_hash.parse::<H256>().unwrap()).unwrap(),
+            apv.wallet.address()
+        );
+        assert!(apv.cancel_order("0x1234").await.is_err());
     }
 
-    pub async fn check_auth(&mut self, jwt: &str) -> eyre::Result<()> {
-        self.last_id += 1;
-        let auth = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_checkAuth",
-            "params": [{
-                "auth": jwt
-            }]
-        });
-        self.request_conn.send_text(auth.to_string()).await?;
-        Ok(())
+    #[tokio::test]
+    async fn fail_order_params() {
+        let mut apv = recording_provider().await;
+        let order_hash = "0x7f0c6bbf6a6e3c5e16f2b2ea93c3f4a4cc95b4dbb2a1d4b0f0a2b5a6d6e0c1f3";
+
+        let id = apv.fail_order(order_hash).await.unwrap();
+        let req = last_request(&apv);
+        assert_eq!(req["id"], id);
+        assert_eq!(req["method"], "aori_failOrder");
+        assert_eq!(req["params"][0]["orderHash"], order_hash);
+        let sig: Signature = req["params"][0]["signature"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            sig.recover(order_hash.parse::<H256>().unwrap()).unwrap(),
+            apv.wallet.address()
+        );
+        assert!(apv.fail_order("not a hash").await.is_err());
     }
 
-    pub async fn view_orderbook(&mut self, base: &str, quote: &str) -> eyre::Result<()> {
-        self.last_id += 1;
-        let req = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_viewOrderbook",
-            "params": [{
-                "chainId": self.chain_id,
-                "query": {
-                    "base": base,
-                    "quote": quote,
-                }
-            }]
+    #[tokio::test]
+    async fn cancel_all_orders_params() {
+        let mut apv = recording_provider().await;
+        apv.cancel_all_orders().await.unwrap();
+
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_cancelAllOrders");
+        assert_eq!(req["params"][0]["address"], *apv.wallet_addr);
+        let sig: Signature = req["params"][0]["signature"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            sig.recover(&*apv.wallet_addr).unwrap(),
+            apv.wallet.address()
+        );
+    }
+
+    #[tokio::test]
+    async fn account_orders_params() {
+        let mut apv = recording_provider().await;
+        apv.account_orders().await.unwrap();
+
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_accountOrders");
+        assert_eq!(req["params"][0]["offerer"], *apv.wallet_addr);
+        assert_eq!(req["params"][0]["signature"], *apv.wallet_sig);
+    }
+
+    #[tokio::test]
+    async fn order_status_params() {
+        let mut apv = recording_provider().await;
+        apv.request_conn
+            .replies
+            .push_back(json!({ "id": 1, "result": "aori_pong" }).to_string());
+        apv.ping().await.unwrap();
+        let id = apv.order_status("0xabc").await.unwrap();
+
+        let req = last_request(&apv);
+        assert_eq!(id, 2);
+        assert_eq!(req["method"], "aori_orderStatus");
+        assert_eq!(req["id"], 2);
+        assert_eq!(req["params"], json!([{ "orderHash": "0xabc" }]));
+    }
+
+    #[tokio::test]
+    async fn request_quote_params() {
+        let mut apv = recording_provider().await;
+        apv.request_quote(
+            "0x2715Ccea428F8c7694f7e78B2C89cb454c5F7294",
+            "0xD3664B5e72B46eaba722aB6f43c22dBF40181954",
+            U256::from(1000000000000000_u128),
+        )
+        .await
+        .unwrap();
+
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_requestQuote");
+        assert_eq!(
+            req["params"],
+            json!([{
+                "chainId": 5,
+                "inputToken": "0x2715Ccea428F8c7694f7e78B2C89cb454c5F7294",
+                "outputToken": "0xD3664B5e72B46eaba722aB6f43c22dBF40181954",
+                "inputAmount": "1000000000000000"
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn get_counter_caches() {
+        let mut apv = recording_provider().await;
+        apv.request_conn
+            .replies
+            .push_back(json!({ "id": 1, "result": { "counter": "7" } }).to_string());
+
+        assert_eq!(apv.fetch_counter().await.unwrap(), U256::from(7));
+        assert_eq!(apv.counter, Some(U256::from(7)));
+        let req = last_request(&apv);
+        assert_eq!(req["method"], "aori_getCounter");
+        assert_eq!(req["params"][0]["address"], *apv.wallet_addr);
+        assert_eq!(req["params"][0]["chainId"], 5);
+    }
+
+    #[tokio::test]
+    async fn recv_typed_response() {
+        let mut apv = recording_provider().await;
+        for reply in [
+            json!({ "id": 1, "result": { "orderHash": "0x01", "status": "active" } }),
+            json!({ "id": 2, "result": { "orderHash": "0x02" } }),
+        ] {
+            apv.request_conn.replies.push_back(reply.to_string());
+        }
+        apv.order_status("0x01").await.unwrap();
+        apv.make_order(sample_order()).await.unwrap();
+
+        // Both replies carry an orderHash; only the one to aori_makeOrder made an order.
+        assert_eq!(
+            apv.recv_response().await.unwrap(),
+            AoriResponse::Other(json!({ "orderHash": "0x01", "status": "active" }))
+        );
+        assert_eq!(
+            apv.recv_response().await.unwrap(),
+            AoriResponse::OrderCreated {
+                order_hash: "0x02".into()
+            }
+        );
+        assert!(apv.request_methods.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replies_matched_by_id() {
+        let mut apv = recording_provider().await;
+        for reply in [
+            json!({ "id": 1, "result": { "orders": [] } }),
+            json!({ "id": 2, "result": "aori_pong" }),
+            json!({ "id": 3, "result": { "auth": "eyJhbGciOi" } }),
+            json!({ "id": 4, "error": { "code": -32000, "message": "invalid jwt" } }),
+        ] {
+            apv.request_conn.replies.push_back(reply.to_string());
+        }
+
+        apv.view_orderbook("WETH", "USDC").await.unwrap();
+        apv.ping().await.unwrap();
+        assert_eq!(apv.auth_wallet().await.unwrap(), "eyJhbGciOi");
+        let err = apv.check_auth("eyJhbGciOi").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::Rpc { code: -32000, .. })
+        ));
+        assert_eq!(
+            apv.recv_response().await.unwrap(),
+            AoriResponse::Orderbook { orders: vec![] }
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_replies_are_bounded() {
+        let mut apv = recording_provider().await;
+        let unread = MAX_PENDING_REPLIES as u64 + 10;
+        for _ in 0..unread {
+            apv.order_status("0xabc").await.unwrap();
+        }
+        for id in 1..=unread + 1 {
+            apv.request_conn
+                .replies
+                .push_back(json!({ "id": id, "result": "aori_pong" }).to_string());
+        }
+        apv.ping().await.unwrap();
+
+        // The replies nobody waited for were held, but only the newest of them.
+        assert_eq!(apv.pending_replies.len(), MAX_PENDING_REPLIES);
+        assert!(apv.request_methods.len() <= MAX_PENDING_REPLIES);
+        assert_eq!(apv.pending_replies.keys().next(), Some(&11));
+        assert_eq!(apv.receive_json().await.unwrap()["id"], 11);
+    }
+
+    #[tokio::test]
+    async fn keepalive_ids_are_bounded() {
+        let mut apv = recording_provider().await;
+        let unread = MAX_PENDING_REPLIES as u64 + 10;
+        for _ in 0..unread {
+            apv.send_keepalive().await.unwrap();
+        }
+
+        // Pongs nobody read don't pile up; only the newest pings are remembered.
+        assert_eq!(apv.keepalive_ids.len(), MAX_PENDING_REPLIES);
+        assert_eq!(apv.keepalive_ids.first(), Some(&11));
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_resubscribes() {
+        let mut apv = recording_provider()
+            .await
+            .with_auto_reconnect(2, Duration::from_millis(1));
+        apv.subscribe_orderbook(false).await.unwrap();
+        apv.request_conn.closed = true;
+        CONNECT_REPLIES.with(|replies| {
+            replies
+                .borrow_mut()
+                .push_back(json!({ "id": 3, "result": { "auth": "eyJhbGciOi" } }).to_string())
         });
-        self.request_conn.send_text(req.to_string()).await?;
-        Ok(())
+
+        apv.order_status("0xabc").await.unwrap();
+
+        let sent: Vec<Value> = apv
+            .request_conn
+            .sent
+            .iter()
+            .map(|payload| serde_json::from_str(payload).unwrap())
+            .collect();
+        assert_eq!(sent[0]["method"], "aori_authWallet");
+        assert_eq!(sent[1]["method"], "aori_orderStatus");
+        let resub: Value = serde_json::from_str(&apv.feed_conn.sent[0]).unwrap();
+        assert_eq!(resub["method"], "aori_subscribeOrderbook");
+        assert_eq!(resub["params"], json!([{ "snapshot": true }]));
+
+        // Ids keep counting through the reconnect instead of starting over.
+        assert_eq!(sent[0]["id"], 3);
+        assert_eq!(sent[1]["id"], 2);
+        assert_eq!(resub["id"], 4);
+        assert_eq!(apv.order_status("0xdef").await.unwrap(), 5);
     }
 
-    pub async fn make_order(&mut self, order_params: OrderComponents) -> eyre::Result<()> {
-        self.last_id += 1;
-        let sig: FixedBytes<32> = order_params.eip712_signing_hash(&SEAPORT_DOMAIN);
-        let signed_sig: Signature = self.wallet.sign_hash(H256::from_slice(sig.as_slice()))?;
-        let order = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_makeOrder",
-            "params": [{
-                "order": {
-                    "signature": format!("0x{}", signed_sig),
-                    "parameters": order_params.to_json()
-                },
-                "isPublic": true,
-                "chainId": self.chain_id
-            }]
+    #[tokio::test]
+    async fn resent_request_keeps_its_method() {
+        let mut apv = recording_provider()
+            .await
+            .with_auto_reconnect(2, Duration::from_millis(1));
+        apv.request_conn.closed = true;
+        CONNECT_REPLIES.with(|replies| {
+            let mut replies = replies.borrow_mut();
+            replies.push_back(json!({ "id": 2, "result": { "auth": "eyJhbGciOi" } }).to_string());
+            replies.push_back(json!({ "id": 1, "result": { "orderHash": "0x01" } }).to_string());
         });
-        self.request_conn.send_text(order.to_string()).await?;
-        Ok(())
+
+        // The first send fails, so the order is resent after re-authenticating.
+        assert_eq!(apv.make_order(sample_order()).await.unwrap(), 1);
+        let resent: Value = serde_json::from_str(&apv.request_conn.sent[1]).unwrap();
+        assert_eq!(resent["method"], "aori_makeOrder");
+
+        assert_eq!(
+            apv.recv_response().await.unwrap(),
+            AoriResponse::OrderCreated {
+                order_hash: "0x01".into()
+            }
+        );
+        assert!(apv.request_methods.is_empty());
     }
 
-    pub async fn subscribe_orderbook(&mut self) -> eyre::Result<()> {
-        self.last_id += 1;
-        let sub_req = json!({
-            "id": self.last_id,
-            "jsonrpc": "2.0",
-            "method": "aori_subscribeOrderbook",
-            "params": []
+    #[tokio::test]
+    async fn reconnect_gives_up_after_max_retries() {
+        let mut apv = recording_provider()
+            .await
+            .with_auto_reconnect(2, Duration::from_millis(1));
+        // Every dial connects, but the relayer drops each connection before auth.
+        apv.request_conn.closed = true;
+        CONNECT_CLOSED.with(|closed| closed.set(true));
+
+        let err = apv.order_status("0xabc").await.unwrap_err();
+        assert!(format!("{:#}", err).contains("failed to reconnect after 3 attempts"));
+        // Each attempt dials the request and feed connections once.
+        assert_eq!(CONNECT_COUNT.with(Cell::get), 2 * 3);
+        assert!(apv.reconnect_policy.is_some());
+    }
+
+    #[tokio::test]
+    async fn subscribe_account_params() {
+        let mut apv = recording_provider()
+            .await
+            .with_auto_reconnect(2, Duration::from_millis(1));
+        let id = apv.subscribe_account().await.unwrap();
+
+        let sub: Value = serde_json::from_str(&apv.feed_conn.sent[0]).unwrap();
+        assert_eq!(sub["id"], id);
+        assert_eq!(sub["method"], "aori_subscribeAccount");
+        assert_eq!(
+            sub["params"],
+            json!([{ "offerer": *apv.wallet_addr, "signature": *apv.wallet_sig }])
+        );
+
+        // Replayed after a reconnect.
+        apv.request_conn.closed = true;
+        CONNECT_REPLIES.with(|replies| {
+            replies
+                .borrow_mut()
+                .push_back(json!({ "id": 3, "result": { "auth": "eyJhbGciOi" } }).to_string())
         });
-        self.feed_conn.send_text(sub_req.to_string()).await?;
-        Ok(())
+        apv.order_status("0xabc").await.unwrap();
+        let resub: Value = serde_json::from_str(&apv.feed_conn.sent[0]).unwrap();
+        assert_eq!(resub["method"], "aori_subscribeAccount");
+        assert_eq!(resub["params"], sub["params"]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloy_primitives::{address, Address, U256};
-    use aori_types::constants::{DEFAULT_CONDUIT_KEY, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH};
-    use aori_types::seaport::{ConsiderationItem, ItemType, OfferItem, OrderComponents, OrderType};
-    use tokio::time::{sleep, Duration};
-    use websockets::Frame;
+    #[tokio::test]
+    async fn orderbook_stream_events() {
+        let mut apv = recording_provider().await;
+        let order = sample_order().to_json();
+        for frame in [
+            json!({ "id": 1, "result": "subscribed" }),
+            json!({ "id": null, "result": { "type": "OrderCreated", "data": {
+                "orderHash": "0x01",
+                "order": { "parameters": order.clone(), "signature": "0xabcd" }
+            } } }),
+            json!({ "id": null, "result": { "type": "OrderCancelled", "data": {
+                "orderHash": "0x01"
+            } } }),
+        ] {
+            apv.feed_conn.replies.push_back(frame.to_string());
+        }
+        apv.subscribe_orderbook(true).await.unwrap();
+
+        let mut events = std::pin::pin!(apv.orderbook_stream());
+        match events.next().await.unwrap().unwrap() {
+            OrderbookEvent::OrderCreated(data) => {
+                assert_eq!(data.order_hash, "0x01");
+                assert_eq!(data.signature.as_deref(), Some("0xabcd"));
+                assert_eq!(data.order.unwrap().to_json(), order);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        match events.next().await.unwrap().unwrap() {
+            OrderbookEvent::OrderCancelled(data) => assert!(data.order.is_none()),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(events.next().await.unwrap().is_err());
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn keepalive_pings_share_ids() {
+        let apv = Arc::new(Mutex::new(recording_provider().await));
+        let keepalive = AoriProvider::spawn_keepalive(apv.clone(), Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(35)).await;
+        keepalive.abort();
+
+        let mut apv = apv.lock().await;
+        let ids: Vec<u64> = apv
+            .request_conn
+            .sent
+            .iter()
+            .map(|payload| serde_json::from_str::<Value>(payload).unwrap())
+            .inspect(|req| assert_eq!(req["method"], "aori_ping"))
+            .map(|req| req["id"].as_u64().unwrap())
+            .collect();
+        assert!(!ids.is_empty());
+        assert_eq!(ids, (1..=ids.len() as u64).collect::<Vec<_>>());
+
+        for id in &ids {
+            apv.request_conn
+                .replies
+                .push_back(json!({ "id": id, "result": "aori_pong" }).to_string());
+        }
+        let next_id = ids.len() as u64 + 1;
+        apv.request_conn
+            .replies
+            .push_back(json!({ "id": next_id, "result": "aori_pong" }).to_string());
+        apv.ping().await.unwrap();
+        assert!(apv.pending_replies.is_empty());
+        assert!(apv.keepalive_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn round_trips_through_mock_server() {
+        let server = MockAoriServer::start().await.unwrap();
+        let mut apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .request_url(&server.url)
+            .feed_url(&server.url)
+            .build()
+            .await
+            .unwrap();
+
+        apv.ping().await.unwrap();
+        let jwt = apv.auth_wallet().await.unwrap();
+        assert_eq!(jwt, MOCK_JWT);
+        apv.check_auth(&jwt).await.unwrap();
+
+        let order = sample_order();
+        let id = apv.make_order(order.clone()).await.unwrap();
+        let reply = apv.wait_for_reply(id).await.unwrap();
+        assert_eq!(
+            reply["result"]["orderHash"],
+            format!("{}", order_hash(&order, 5))
+        );
+
+        let err = apv.market_status("WETH", "USDC").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::Rpc { code: -32601, .. })
+        ));
+        assert_eq!(
+            server.methods(),
+            [
+                "aori_ping",
+                "aori_authWallet",
+                "aori_checkAuth",
+                "aori_makeOrder",
+                "aori_marketStatus"
+            ]
+        );
+        apv.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn times_out_on_silent_server() {
+        let server = MockAoriServer::start().await.unwrap();
+        let mut apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .request_url(&server.url)
+            .feed_url(&server.url)
+            .with_request_timeout(Duration::from_millis(50))
+            .build()
+            .await
+            .unwrap();
+
+        apv.ping().await.unwrap();
+        server.set_silent(true);
+        let err = apv.ping().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::Timeout(timeout)) if *timeout == Duration::from_millis(50)
+        ));
+    }
+
+    #[tokio::test]
+    async fn drives_alternate_backend() {
+        let mut request_conn = RecordingWs::default();
+        request_conn
+            .replies
+            .push_back(json!({ "id": 1, "result": "aori_pong" }).to_string());
+        request_conn
+            .replies
+            .push_back(json!({ "id": 2, "result": { "status": "halted" } }).to_string());
+        let mut apv =
+            AoriProvider::from_connections(request_conn, RecordingWs::default(), test_wallet(), 5)
+                .await
+                .unwrap();
+
+        apv.ping().await.unwrap();
+        let status = apv.market_status("WETH", "USDC").await.unwrap();
+        apv.subscribe_orderbook(true).await.unwrap();
+
+        assert_eq!(status, MarketStatus::Halted);
+        let sent: Vec<Value> = apv
+            .request_conn
+            .sent
+            .iter()
+            .map(|payload| serde_json::from_str(payload).unwrap())
+            .collect();
+        assert_eq!(sent[0]["method"], "aori_ping");
+        assert_eq!(sent[1]["method"], "aori_marketStatus");
+        assert_eq!(sent[1]["id"], 2);
+        assert_eq!(apv.feed_conn.sent.len(), 1);
+    }
 
     #[tokio::test]
     async fn generate_order_sig() {
@@ -193,7 +2501,7 @@ mod tests {
             counter: U256::from(0),
         };
 
-        let params_sig = order_components.eip712_signing_hash(&*SEAPORT_DOMAIN);
+        let params_sig = order_components.eip712_signing_hash(&apv.seaport_domain);
 
         /*
         https://docs.rs/ethers/latest/ethers/signers/struct.Wallet.html#method.sign_typed_data
@@ -219,6 +2527,210 @@ mod tests {
         println!("0x{}", signed_slice);
     }
 
+    #[test]
+    fn visibility_params() {
+        let mut public = json!({ "chainId": 5 });
+        OrderVisibility::Public.apply(&mut public);
+        assert_eq!(public, json!({ "chainId": 5, "isPublic": true }));
+
+        let mut private = json!({ "chainId": 5 });
+        OrderVisibility::Private.apply(&mut private);
+        assert_eq!(private, json!({ "chainId": 5, "isPublic": false }));
+    }
+
+    #[tokio::test]
+    async fn request_ids_increment() {
+        let mut apv = recording_provider().await;
+        let first = apv.build_request("aori_ping", json!([]));
+        let second = apv.build_request("aori_ping", json!([]));
+        assert_eq!(first["jsonrpc"], "2.0");
+        assert_eq!(first["method"], "aori_ping");
+        assert_eq!(
+            second["id"].as_u64().unwrap(),
+            first["id"].as_u64().unwrap() + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn jsonrpc_version_override() {
+        let mut apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .jsonrpc_version("2.1")
+            .build_with_backend::<RecordingWs>()
+            .await
+            .unwrap();
+        let req = apv.build_request("aori_ping", json!([]));
+        assert_eq!(req["jsonrpc"], "2.1");
+
+        let mut apv = recording_provider().await;
+        let req = apv.build_request("aori_ping", json!([]));
+        assert_eq!(req["jsonrpc"], "2.0");
+    }
+
+    #[test]
+    fn parse_market_status() {
+        let active = json!({ "id": 1, "result": { "status": "active" } });
+        let halted = json!({ "id": 2, "result": { "status": "halted" } });
+        assert_eq!(
+            MarketStatus::deserialize(active.pointer("/result/status").unwrap()).unwrap(),
+            MarketStatus::Active
+        );
+        assert_eq!(
+            MarketStatus::deserialize(halted.pointer("/result/status").unwrap()).unwrap(),
+            MarketStatus::Halted
+        );
+        assert!(serde_json::from_value::<MarketStatus>(json!("paused")).is_err());
+    }
+
+    #[tokio::test]
+    async fn audit_hook_records_signed_digest() {
+        let records = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let captured = records.clone();
+        let mut apv = recording_provider()
+            .await
+            .with_audit_hook(move |record: &SigningRecord| {
+                captured.lock().unwrap().push(record.clone())
+            });
+        let order = sample_order();
+        apv.make_order(order.clone()).await.unwrap();
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.digest, order_hash(&order, 5));
+        let mut preimage = vec![0x19, 0x01];
+        preimage.extend_from_slice(record.domain_separator.as_slice());
+        preimage.extend_from_slice(record.struct_hash.as_slice());
+        assert_eq!(keccak256(preimage), record.digest);
+
+        let signer = record
+            .signature
+            .recover(H256::from_slice(record.digest.as_slice()))
+            .unwrap();
+        assert_eq!(ethers::utils::to_checksum(&signer, None), *apv.wallet_addr);
+        assert_eq!(
+            last_request(&apv)["params"][0]["order"]["signature"],
+            format!("0x{}", record.signature)
+        );
+    }
+
+    #[test]
+    fn subscribe_filters() {
+        assert_eq!(
+            filtered_subscribe_params(Some("WETH"), Some("USDC"), Some(5)),
+            json!([{ "base": "WETH", "quote": "USDC", "chainId": 5 }])
+        );
+        assert_eq!(
+            filtered_subscribe_params(None, Some("USDC"), None),
+            json!([{ "quote": "USDC" }])
+        );
+        assert_eq!(filtered_subscribe_params(None, None, None), json!([{}]));
+    }
+
+    #[test]
+    fn orderbook_query_filters() {
+        assert_eq!(
+            orderbook_query("WETH", "USDC", None, None, None),
+            json!({ "base": "WETH", "quote": "USDC" })
+        );
+        assert_eq!(
+            orderbook_query(
+                "WETH",
+                "USDC",
+                Some(Side::Ask),
+                Some(5),
+                Some(SortOrder::Asc)
+            ),
+            json!({
+                "base": "WETH",
+                "quote": "USDC",
+                "side": "ask",
+                "limit": 5,
+                "sort": "asc"
+            })
+        );
+    }
+
+    #[test]
+    fn subscribe_snapshot_flag() {
+        assert_eq!(
+            subscribe_orderbook_params(true),
+            json!([{ "snapshot": true }])
+        );
+        assert_eq!(
+            subscribe_orderbook_params(false),
+            json!([{ "snapshot": false }])
+        );
+    }
+
+    #[tokio::test]
+    async fn builder_sets_verifying_contract() {
+        let contract = address!("1111111111111111111111111111111111111111");
+        let apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .with_verifying_contract(contract)
+            .build_with_backend::<RecordingWs>()
+            .await
+            .unwrap();
+        assert_eq!(apv.seaport_domain, seaport_domain_for(5, contract).unwrap());
+
+        let err = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .with_verifying_contract(Address::ZERO)
+            .build_with_backend::<RecordingWs>()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("non-zero address"));
+    }
+
+    #[tokio::test]
+    async fn builder_sets_max_order_lifetime() {
+        let mut apv = AoriProviderBuilder::new()
+            .private_key("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .chain_id(5)
+            .with_max_order_lifetime(Duration::from_secs(60 * 60))
+            .build_with_backend::<RecordingWs>()
+            .await
+            .unwrap();
+        assert_eq!(apv.max_order_lifetime, Some(60 * 60));
+
+        // sample_order lives exactly an hour.
+        apv.make_order(sample_order()).await.unwrap();
+        let sent = apv.request_conn.sent.len();
+        let mut long_lived = sample_order();
+        long_lived.endTime += U256::from(1);
+        let err = apv.make_order(long_lived).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AoriError>(),
+            Some(AoriError::InvalidOrder(_))
+        ));
+        assert_eq!(apv.request_conn.sent.len(), sent);
+    }
+
+    #[test]
+    fn order_lifetime_limit() {
+        let mut order_params = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202 + 60 * 60),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        assert!(check_order_lifetime(&order_params, 24 * 60 * 60).is_ok());
+
+        order_params.endTime = U256::from(1697240202 + 48 * 60 * 60);
+        assert!(check_order_lifetime(&order_params, 24 * 60 * 60).is_err());
+    }
+
     #[tokio::test]
     async fn test_connection() {
         dotenv::dotenv().ok();
@@ -226,8 +2738,6 @@ mod tests {
             .await
             .expect("Failed to create Aori Provider");
         apv.ping().await.unwrap();
-        let response = format!("{:#?}", apv.request_conn.receive().await.unwrap());
-        println!("{response:}");
     }
 
     #[tokio::test]
@@ -236,22 +2746,9 @@ mod tests {
         let mut apv = AoriProvider::new_from_env()
             .await
             .expect("Failed to create Aori Provider");
-        apv.auth_wallet().await.unwrap();
-        let frame: Frame = apv.request_conn.receive().await.unwrap();
-
-        let payload: String = match frame {
-            Frame::Text { payload, .. } => Some(payload),
-            _ => None,
-        }
-        .unwrap();
-        let resp_value: serde_json::Value = serde_json::from_str(&payload).unwrap();
-        println!("{:#?}", resp_value);
-        let jwt = resp_value.pointer("/result/auth").unwrap().to_string();
-        apv.check_auth(jwt.as_str()).await.unwrap();
-        sleep(Duration::from_millis(100)).await;
-        let check = format!("{:#?}", apv.request_conn.receive().await.unwrap());
+        let jwt = apv.auth_wallet().await.unwrap();
+        apv.check_auth(&jwt).await.unwrap();
         println!("jwt > {}", jwt);
-        println!(" check > {check:}");
     }
 
     #[tokio::test]
@@ -260,8 +2757,8 @@ mod tests {
         let wallet = std::env::var("WALLET_ADDRESS")
             .context("missing WALLET_ADDRESS")
             .unwrap();
-        let start_time = chrono::Utc::now().timestamp_millis();
-        let end_time = chrono::Utc::now().timestamp_millis() + 1000 * 60 * 60 * 24;
+        let start_time = Timestamp::now();
+        let end_time = secs_from_now(Duration::from_secs(60 * 60 * 24));
         let mut apv = AoriProvider::new_from_env()
             .await
             .expect("Failed to create Aori Provider");
@@ -286,8 +2783,8 @@ mod tests {
             offer: vec![offer_item.clone()],
             consideration: vec![consider_item.clone()],
             orderType: OrderType::PARTIAL_RESTRICTED as u8,
-            startTime: U256::from(start_time),
-            endTime: U256::from(end_time),
+            startTime: start_time.into(),
+            endTime: end_time.into(),
             zoneHash: DEFAULT_ZONE_HASH.into(),
             salt: U256::from(0),
             conduitKey: DEFAULT_CONDUIT_KEY.into(),
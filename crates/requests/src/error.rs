@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+use thiserror::Error;
+
+use aori_types::validate::OrderValidationError;
+
+/// How long to back off when a rate limit error doesn't say.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// The message jsonwebtoken gives for an expired JWT, which the relayer passes through
+/// with a generic error code. Other messages that mention expiry, like an expired
+/// order, are not auth failures.
+const JWT_EXPIRED_MESSAGE: &str = "jwt expired";
+
+/// Failures a bot may want to react to. Methods still return `eyre::Result`; match on
+/// these with `err.downcast_ref::<AoriError>()`.
+#[derive(Debug, Error)]
+pub enum AoriError {
+    #[error("auth token has expired")]
+    AuthExpired,
+    #[error("rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
+    #[error("invalid order: {0}")]
+    InvalidOrder(String),
+    /// The order failed `OrderComponents::validate` and was never signed or sent.
+    #[error("invalid order: {0}")]
+    Validation(#[from] OrderValidationError),
+    #[error("websocket error: {0}")]
+    WebSocket(#[from] websockets::WebSocketError),
+    #[error("websocket closed by the server")]
+    ConnectionClosed,
+    #[error("rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("no reply within {0:?}")]
+    Timeout(Duration),
+    /// The signer couldn't sign a bare order hash, as `cancel_order` and `fail_order`
+    /// need. Hardware wallets such as Ledger and Trezor only sign full typed data.
+    #[error("signer can't sign a raw order hash: {0}")]
+    RawDigestUnsupported(String),
+}
+
+impl AoriError {
+    /// Classifies the `error` object of a JSON-RPC reply. Aori doesn't use distinct
+    /// codes for every failure, so the message is checked as well.
+    pub fn from_rpc_error(error: &Value) -> Self {
+        let code = error
+            .get("code")
+            .and_then(Value::as_i64)
+            .unwrap_or_default();
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let lowercase = message.to_lowercase();
+
+        if code == 429 || lowercase.contains("rate limit") {
+            let retry_after = error
+                .pointer("/data/retryAfter")
+                .and_then(Value::as_u64)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_RETRY_AFTER);
+            AoriError::RateLimited { retry_after }
+        } else if code == 401 || lowercase.trim() == JWT_EXPIRED_MESSAGE {
+            AoriError::AuthExpired
+        } else if lowercase.contains("invalid order") {
+            AoriError::InvalidOrder(message)
+        } else {
+            AoriError::Rpc { code, message }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn classifies_rpc_errors() {
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": 429, "message": "slow down" })),
+            AoriError::RateLimited { retry_after } if retry_after == DEFAULT_RETRY_AFTER
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({
+                "code": -32000,
+                "message": "Rate limit exceeded",
+                "data": { "retryAfter": 5 }
+            })),
+            AoriError::RateLimited { retry_after } if retry_after == Duration::from_secs(5)
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": -32000, "message": "JWT expired" })),
+            AoriError::AuthExpired
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": 401, "message": "unauthorized" })),
+            AoriError::AuthExpired
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": -32000, "message": "Order expired" })),
+            AoriError::Rpc { code: -32000, message } if message == "Order expired"
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": -32000, "message": "Invalid order: bad signature" })),
+            AoriError::InvalidOrder(message) if message == "Invalid order: bad signature"
+        ));
+        assert!(matches!(
+            AoriError::from_rpc_error(&json!({ "code": -32601, "message": "method not found" })),
+            AoriError::Rpc { code: -32601, .. }
+        ));
+    }
+}
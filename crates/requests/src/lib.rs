@@ -1 +1,8 @@
 pub mod aori_provider;
+pub mod error;
+pub mod rate_limit;
+pub mod response;
+mod signer;
+#[cfg(test)]
+mod testutil;
+pub mod ws_backend;
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+/// Token bucket holding up to `per_second` sends and refilling at `per_second` a
+/// second. A send that finds it empty is scheduled after the sends already waiting
+/// rather than rejected.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_second: f64,
+    /// Goes negative while sends are waiting, by one for each.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(per_second: u32) -> Self {
+        let per_second = f64::from(per_second.max(1));
+        Self {
+            per_second,
+            tokens: per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token and returns how long to wait before using it.
+    pub fn reserve(&mut self) -> Duration {
+        self.reserve_at(Instant::now())
+    }
+
+    fn reserve_at(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.per_second).min(self.per_second);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.per_second)
+        }
+    }
+
+    /// Waits until a send is allowed.
+    pub async fn acquire(&mut self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_bursts_then_spaces_sends() {
+        let mut limiter = RateLimiter::new(2);
+        let start = limiter.last_refill;
+
+        assert_eq!(limiter.reserve_at(start), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(start), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(start), Duration::from_millis(500));
+        assert_eq!(limiter.reserve_at(start), Duration::from_secs(1));
+
+        // Three seconds later the queued sends are paid for and the bucket is full again.
+        let later = start + Duration::from_secs(3);
+        assert_eq!(limiter.reserve_at(later), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(later), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(later), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,284 @@
+use serde_json::Value;
+
+use aori_types::seaport::OrderComponents;
+
+/// A reply on the request connection, classified by the method of the request it
+/// answers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AoriResponse {
+    Pong,
+    AuthResult {
+        auth: String,
+    },
+    Orderbook {
+        orders: Vec<Value>,
+    },
+    OrderCreated {
+        order_hash: String,
+    },
+    Error {
+        code: i64,
+        message: String,
+    },
+    /// A successful reply to any other method, or to a request this client didn't send.
+    Other(Value),
+}
+
+impl AoriResponse {
+    /// Classifies `resp`, the reply to a request for `method`. Replies are told apart by
+    /// what was asked rather than by their shape, since different methods return
+    /// overlapping fields such as `orderHash`.
+    pub fn from_json(method: Option<&str>, resp: &Value) -> eyre::Result<Self> {
+        if let Some(error) = resp.get("error") {
+            return Ok(AoriResponse::Error {
+                code: error
+                    .get("code")
+                    .and_then(Value::as_i64)
+                    .unwrap_or_default(),
+                message: error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+        let result = resp
+            .get("result")
+            .ok_or_else(|| eyre::eyre!("response has neither result nor error: {}", resp))?;
+
+        let field = |key: &str| {
+            result
+                .get(key)
+                .ok_or_else(|| eyre::eyre!("result has no {}: {}", key, result))
+        };
+        Ok(match method {
+            Some("aori_ping") => AoriResponse::Pong,
+            Some("aori_authWallet") => AoriResponse::AuthResult {
+                auth: field("auth")?
+                    .as_str()
+                    .ok_or_else(|| eyre::eyre!("auth token is not a string: {}", result))?
+                    .to_string(),
+            },
+            Some("aori_viewOrderbook") => AoriResponse::Orderbook {
+                orders: field("orders")?
+                    .as_array()
+                    .ok_or_else(|| eyre::eyre!("orders is not an array: {}", result))?
+                    .clone(),
+            },
+            Some("aori_makeOrder") => AoriResponse::OrderCreated {
+                order_hash: field("orderHash")?
+                    .as_str()
+                    .ok_or_else(|| eyre::eyre!("orderHash is not a string: {}", result))?
+                    .to_string(),
+            },
+            _ => AoriResponse::Other(result.clone()),
+        })
+    }
+}
+
+/// An order as carried by an orderbook feed event. Events that only identify the order,
+/// such as most cancellations, have no `order` or `signature`.
+#[derive(Debug, Clone)]
+pub struct OrderData {
+    pub order_hash: String,
+    pub order: Option<OrderComponents>,
+    pub signature: Option<String>,
+}
+
+impl OrderData {
+    pub fn from_json(data: &Value) -> eyre::Result<Self> {
+        let order_hash = data
+            .get("orderHash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| eyre::eyre!("orderbook event has no orderHash: {}", data))?
+            .to_string();
+        let order = data
+            .pointer("/order/parameters")
+            .map(OrderComponents::from_json)
+            .transpose()?;
+        let signature = data
+            .pointer("/order/signature")
+            .and_then(Value::as_str)
+            .map(String::from);
+        Ok(Self {
+            order_hash,
+            order,
+            signature,
+        })
+    }
+}
+
+/// A signed order resting on the book.
+#[derive(Debug, Clone)]
+pub struct RestingOrder {
+    pub order_hash: String,
+    pub order: OrderComponents,
+    pub signature: String,
+}
+
+impl RestingOrder {
+    pub fn from_json(data: &Value) -> eyre::Result<Self> {
+        let OrderData {
+            order_hash,
+            order,
+            signature,
+        } = OrderData::from_json(data)?;
+        let (Some(order), Some(signature)) = (order, signature) else {
+            eyre::bail!("resting order {} has no signed order", order_hash);
+        };
+        Ok(Self {
+            order_hash,
+            order,
+            signature,
+        })
+    }
+
+    /// Parses the `orders` of an `aori_viewOrderbook` result.
+    pub fn from_orderbook_result(result: &Value) -> eyre::Result<Vec<Self>> {
+        result
+            .get("orders")
+            .and_then(Value::as_array)
+            .ok_or_else(|| eyre::eyre!("orderbook result has no orders: {}", result))?
+            .iter()
+            .map(Self::from_json)
+            .collect()
+    }
+}
+
+/// Both sides of one market's book, best price first.
+#[derive(Debug, Clone, Default)]
+pub struct Orderbook {
+    pub bids: Vec<RestingOrder>,
+    pub asks: Vec<RestingOrder>,
+}
+
+/// An update pushed on the feed connection after `subscribe_orderbook`.
+#[derive(Debug, Clone)]
+pub enum OrderbookEvent {
+    OrderCreated(OrderData),
+    OrderCancelled(OrderData),
+    OrderTaken(OrderData),
+    OrderUpdated(OrderData),
+}
+
+impl OrderbookEvent {
+    /// Parses a feed frame of the form `{"result": {"type": ..., "data": ...}}`.
+    /// Frames that aren't orderbook events, like the subscription ack, give `None`.
+    pub fn from_json(frame: &Value) -> eyre::Result<Option<Self>> {
+        let (Some(kind), Some(data)) = (
+            frame.pointer("/result/type").and_then(Value::as_str),
+            frame.pointer("/result/data"),
+        ) else {
+            return Ok(None);
+        };
+        let event: fn(OrderData) -> Self = match kind {
+            "OrderCreated" => OrderbookEvent::OrderCreated,
+            "OrderCancelled" => OrderbookEvent::OrderCancelled,
+            "OrderTaken" => OrderbookEvent::OrderTaken,
+            "OrderUpdated" => OrderbookEvent::OrderUpdated,
+            _ => return Ok(None),
+        };
+        Ok(Some(event(OrderData::from_json(data)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dispatch_by_method() {
+        let parse =
+            |method: &str, resp: Value| AoriResponse::from_json(Some(method), &resp).unwrap();
+
+        assert_eq!(
+            parse("aori_ping", json!({ "id": 1, "result": "aori_pong" })),
+            AoriResponse::Pong
+        );
+        assert_eq!(
+            parse(
+                "aori_authWallet",
+                json!({ "id": 2, "result": { "auth": "eyJhbGciOi" } })
+            ),
+            AoriResponse::AuthResult {
+                auth: "eyJhbGciOi".into()
+            }
+        );
+        assert_eq!(
+            parse(
+                "aori_viewOrderbook",
+                json!({ "id": 3, "result": { "orders": [{ "orderHash": "0x01" }] } })
+            ),
+            AoriResponse::Orderbook {
+                orders: vec![json!({ "orderHash": "0x01" })]
+            }
+        );
+        assert_eq!(
+            parse(
+                "aori_makeOrder",
+                json!({ "id": 4, "result": { "orderHash": "0x02" } })
+            ),
+            AoriResponse::OrderCreated {
+                order_hash: "0x02".into()
+            }
+        );
+        assert_eq!(
+            parse(
+                "aori_makeOrder",
+                json!({ "id": 5, "error": { "code": -32000, "message": "invalid order" } })
+            ),
+            AoriResponse::Error {
+                code: -32000,
+                message: "invalid order".into()
+            }
+        );
+        // An order status carries an orderHash too, but no order was created.
+        assert_eq!(
+            parse(
+                "aori_orderStatus",
+                json!({ "id": 6, "result": { "orderHash": "0x02", "status": "filled" } })
+            ),
+            AoriResponse::Other(json!({ "orderHash": "0x02", "status": "filled" }))
+        );
+        assert_eq!(
+            AoriResponse::from_json(None, &json!({ "id": 7, "result": { "auth": "x" } })).unwrap(),
+            AoriResponse::Other(json!({ "auth": "x" }))
+        );
+        assert!(
+            AoriResponse::from_json(Some("aori_makeOrder"), &json!({ "id": 8, "result": {} }))
+                .is_err()
+        );
+        assert!(AoriResponse::from_json(Some("aori_ping"), &json!({ "id": 9 })).is_err());
+    }
+
+    #[test]
+    fn resting_orders_need_a_signed_order() {
+        let order = json!({
+            "offerer": "0x1111111111111111111111111111111111111111",
+            "zone": "0x0000000000000000000000000000000000000000",
+            "offer": [],
+            "consideration": [],
+            "orderType": 0,
+            "startTime": "1697240202",
+            "endTime": "1697243802",
+            "zoneHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "salt": "0",
+            "conduitKey": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "counter": "0"
+        });
+        let result = json!({ "orders": [{
+            "orderHash": "0x01",
+            "order": { "parameters": order, "signature": "0xabcd" }
+        }] });
+        let orders = RestingOrder::from_orderbook_result(&result).unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].order_hash, "0x01");
+        assert_eq!(orders[0].signature, "0xabcd");
+        assert_eq!(orders[0].order.startTime.to::<u64>(), 1697240202);
+
+        let unsigned = json!({ "orders": [{ "orderHash": "0x02" }] });
+        assert!(RestingOrder::from_orderbook_result(&unsigned).is_err());
+        assert!(RestingOrder::from_orderbook_result(&json!({})).is_err());
+    }
+}
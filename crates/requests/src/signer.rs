@@ -0,0 +1,129 @@
+use alloy_primitives::keccak256;
+use alloy_sol_types::{Eip712Domain, SolStruct};
+use ethers::types::{
+    transaction::eip712::{EIP712Domain, Eip712, Eip712Error},
+    H160, H256, U256,
+};
+
+/// An alloy `SolStruct` under an alloy domain, in the form ethers' `Signer::sign_typed_data`
+/// takes, so any `Signer` (KMS, Ledger, a remote service) can sign orders and permits.
+pub(crate) struct TypedData<'a, T> {
+    value: &'a T,
+    domain: &'a Eip712Domain,
+}
+
+impl<'a, T: SolStruct> TypedData<'a, T> {
+    pub(crate) fn new(value: &'a T, domain: &'a Eip712Domain) -> Self {
+        Self { value, domain }
+    }
+}
+
+impl<T: SolStruct> Eip712 for TypedData<'_, T> {
+    type Error = Eip712Error;
+
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.domain.separator().0)
+    }
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: self.domain.name.as_ref().map(|name| name.to_string()),
+            version: self
+                .domain
+                .version
+                .as_ref()
+                .map(|version| version.to_string()),
+            chain_id: self
+                .domain
+                .chain_id
+                .map(|chain_id| U256::from_big_endian(&chain_id.to_be_bytes::<32>())),
+            verifying_contract: self
+                .domain
+                .verifying_contract
+                .map(|contract| H160::from_slice(contract.as_slice())),
+            salt: self.domain.salt.map(|salt| salt.0),
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(T::eip712_encode_type().as_bytes()).0)
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.value.eip712_hash_struct().0)
+    }
+}
+
+/// A digest the server expects signed as-is, such as an order hash for cancellation.
+/// Signers that sign the digest `encode_eip712` returns (local and KMS wallets) accept
+/// it. There is no domain or struct behind the digest, so signers that need them
+/// separately (Ledger, Trezor) fail, which `AoriProvider` reports as
+/// `AoriError::RawDigestUnsupported`.
+pub(crate) struct RawDigest(pub(crate) H256);
+
+impl Eip712 for RawDigest {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Err(Eip712Error::Message("raw digest has no domain".to_string()))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Err(Eip712Error::Message("raw digest has no type".to_string()))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Err(Eip712Error::Message(
+            "raw digest has no struct hash".to_string(),
+        ))
+    }
+
+    fn encode_eip712(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.0 .0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, U256};
+    use aori_types::{
+        builder::erc20_swap_order,
+        seaport::{seaport_domain, OrderComponents},
+    };
+
+    #[test]
+    fn typed_data_matches_alloy_hashes() {
+        let order = erc20_swap_order(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000000000000000_u128),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500000),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        let domain = seaport_domain(5);
+        let typed = TypedData::new(&order, &domain);
+
+        // The converted domain must hash the same as the alloy one for signers that
+        // rebuild the separator themselves.
+        assert_eq!(typed.domain().unwrap().separator(), domain.separator().0);
+        assert_eq!(
+            typed.encode_eip712().unwrap(),
+            order.eip712_signing_hash(&domain).0
+        );
+        assert_eq!(
+            TypedData::<OrderComponents>::type_hash().unwrap(),
+            order.eip712_type_hash().0
+        );
+    }
+
+    #[test]
+    fn raw_digest_only_encodes() {
+        let digest = RawDigest(H256::repeat_byte(0xab));
+        assert_eq!(digest.encode_eip712().unwrap(), [0xab; 32]);
+        assert!(digest.domain().is_err());
+        assert!(digest.struct_hash().is_err());
+    }
+}
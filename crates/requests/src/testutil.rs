@@ -0,0 +1,130 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, task::JoinHandle};
+use tokio_tungstenite::tungstenite::Message;
+
+use aori_types::seaport::{order_hash, OrderComponents};
+
+/// JWT the mock server issues for every `aori_authWallet`.
+pub(crate) const MOCK_JWT: &str = "eyJhbGciOiJIUzI1NiJ9.e30.mock";
+
+/// In-process WebSocket server speaking enough of the Aori JSON-RPC dialect to drive a
+/// real `AoriProvider` end to end: `aori_ping`, `aori_authWallet`, `aori_checkAuth`,
+/// `aori_makeOrder` and `aori_subscribeOrderbook`, singly or batched. Anything else gets
+/// a method-not-found error. Serves any number of connections on one port until dropped.
+pub(crate) struct MockAoriServer {
+    pub url: String,
+    /// Every request received, across all connections, in arrival order.
+    pub received: Arc<Mutex<Vec<Value>>>,
+    /// While set, requests are logged but never answered.
+    silent: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl MockAoriServer {
+    pub async fn start() -> eyre::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let silent = Arc::new(AtomicBool::new(false));
+        let log = received.clone();
+        let muted = silent.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let log = log.clone();
+                let muted = muted.clone();
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    while let Some(Ok(msg)) = ws.next().await {
+                        let Message::Text(text) = msg else {
+                            continue;
+                        };
+                        let Ok(req) = serde_json::from_str::<Value>(&text) else {
+                            continue;
+                        };
+                        let reply = match &req {
+                            Value::Array(batch) => batch.iter().map(reply_to).collect(),
+                            req => reply_to(req),
+                        };
+                        log.lock().unwrap().push(req);
+                        if muted.load(Ordering::SeqCst) {
+                            continue;
+                        }
+                        if ws.send(Message::Text(reply.to_string())).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            url,
+            received,
+            silent,
+            handle,
+        })
+    }
+
+    /// Stops answering requests, as a wedged relayer would, or starts again.
+    pub fn set_silent(&self, silent: bool) {
+        self.silent.store(silent, Ordering::SeqCst);
+    }
+
+    /// Methods of the requests received so far, with batches flattened.
+    pub fn methods(&self) -> Vec<String> {
+        let received = self.received.lock().unwrap();
+        received
+            .iter()
+            .flat_map(|req| match req {
+                Value::Array(batch) => batch.clone(),
+                req => vec![req.clone()],
+            })
+            .filter_map(|req| req["method"].as_str().map(String::from))
+            .collect()
+    }
+}
+
+impl Drop for MockAoriServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn reply_to(req: &Value) -> Value {
+    let id = req["id"].clone();
+    let result = match req["method"].as_str() {
+        Some("aori_ping") => json!("aori_pong"),
+        Some("aori_authWallet") => json!({ "auth": MOCK_JWT }),
+        Some("aori_checkAuth") => json!("ok"),
+        Some("aori_subscribeOrderbook") => json!("subscribed"),
+        Some("aori_makeOrder") => match made_order_hash(&req["params"][0]) {
+            Ok(hash) => json!({ "orderHash": hash }),
+            Err(err) => return rpc_error(id, -32602, &format!("invalid order: {}", err)),
+        },
+        _ => return rpc_error(id, -32601, "method not found"),
+    };
+    json!({ "id": id, "jsonrpc": "2.0", "result": result })
+}
+
+fn made_order_hash(params: &Value) -> eyre::Result<String> {
+    let order = OrderComponents::from_json(&params["order"]["parameters"])?;
+    let chain_id = params["chainId"]
+        .as_u64()
+        .ok_or_else(|| eyre::eyre!("missing chainId"))?;
+    Ok(format!("{}", order_hash(&order, chain_id)))
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "id": id,
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message }
+    })
+}
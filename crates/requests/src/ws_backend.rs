@@ -0,0 +1,51 @@
+use std::future::Future;
+
+use websockets::{Frame, WebSocket};
+
+use crate::error::AoriError;
+
+/// The WebSocket operations `AoriProvider` needs. Implemented here for the
+/// `websockets` crate; implement it for another client (e.g. `tokio-tungstenite`)
+/// to get proxy, compression or custom TLS support the default backend lacks.
+pub trait WsBackend: Sized + Send {
+    fn connect(url: &str) -> impl Future<Output = eyre::Result<Self>> + Send;
+
+    fn send_text(&mut self, payload: String) -> impl Future<Output = eyre::Result<()>> + Send;
+
+    /// Waits for the next text message, skipping control frames. A close from the
+    /// server is an error.
+    fn receive_text(&mut self) -> impl Future<Output = eyre::Result<String>> + Send;
+
+    fn close(&mut self) -> impl Future<Output = eyre::Result<()>> + Send;
+}
+
+impl WsBackend for WebSocket {
+    async fn connect(url: &str) -> eyre::Result<Self> {
+        Ok(WebSocket::connect(url).await.map_err(AoriError::from)?)
+    }
+
+    async fn send_text(&mut self, payload: String) -> eyre::Result<()> {
+        Ok(WebSocket::send_text(self, payload)
+            .await
+            .map_err(AoriError::from)?)
+    }
+
+    async fn receive_text(&mut self) -> eyre::Result<String> {
+        loop {
+            match WebSocket::receive(self).await.map_err(AoriError::from)? {
+                Frame::Text { payload, .. } => return Ok(payload),
+                Frame::Close { payload } => {
+                    return Err(eyre::Report::new(AoriError::ConnectionClosed)
+                        .wrap_err(format!("close frame: {:?}", payload)))
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn close(&mut self) -> eyre::Result<()> {
+        Ok(WebSocket::close(self, None)
+            .await
+            .map_err(AoriError::from)?)
+    }
+}
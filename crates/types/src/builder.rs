@@ -0,0 +1,546 @@
+use std::time::Duration;
+
+use alloy_primitives::{Address, FixedBytes, U256};
+
+use crate::constants::{
+    DEFAULT_CONDUIT_KEY, DEFAULT_DURATION, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH,
+};
+use crate::salt::random_salt;
+use crate::seaport::{ConsiderationItem, ItemType, OfferItem, OrderComponents, OrderType};
+use crate::time::Timestamp;
+use crate::validate::{has_custom_zone, is_restricted};
+
+/// Builds an `OrderComponents` field by field. `zone`, `zoneHash` and `conduitKey`
+/// default to the crate constants, the order runs from now for `DEFAULT_DURATION`,
+/// the salt is random and the counter is zero unless set.
+#[derive(Debug, Clone)]
+pub struct OrderComponentsBuilder {
+    offerer: Address,
+    zone: Address,
+    offer: Vec<OfferItem>,
+    consideration: Vec<ConsiderationItem>,
+    order_type: OrderType,
+    timespan: Option<(Timestamp, Timestamp)>,
+    zone_hash: FixedBytes<32>,
+    salt: Option<U256>,
+    conduit_key: FixedBytes<32>,
+    counter: U256,
+    /// Set when a decay method is called before any item it could apply to.
+    misuse: Option<&'static str>,
+}
+
+impl Default for OrderComponentsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderComponentsBuilder {
+    pub fn new() -> Self {
+        Self {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            order_type: OrderType::PARTIAL_RESTRICTED,
+            timespan: None,
+            zone_hash: DEFAULT_ZONE_HASH.into(),
+            salt: None,
+            conduit_key: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+            misuse: None,
+        }
+    }
+
+    pub fn offerer(mut self, offerer: Address) -> Self {
+        self.offerer = offerer;
+        self
+    }
+
+    /// Zone that validates the order on fill, e.g. for restricted orders with on-chain
+    /// checks. A zone other than the default needs a `*_RESTRICTED` `order_type`.
+    pub fn zone(mut self, zone: Address) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    pub fn zone_hash(mut self, zone_hash: FixedBytes<32>) -> Self {
+        self.zone_hash = zone_hash;
+        self
+    }
+
+    pub fn conduit_key(mut self, conduit_key: FixedBytes<32>) -> Self {
+        self.conduit_key = conduit_key;
+        self
+    }
+
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = order_type;
+        self
+    }
+
+    /// Offers a fixed `amount` of the ERC20 `token`.
+    pub fn offer_erc20(mut self, token: Address, amount: U256) -> Self {
+        self.offer.push(OfferItem {
+            itemType: ItemType::ERC20 as u8,
+            token,
+            identifierOrCriteria: U256::from(0),
+            startAmount: amount,
+            endAmount: amount,
+        });
+        self
+    }
+
+    /// Offers the ERC721 `token_id` of `token`. ERC721 items always have an amount of 1.
+    pub fn offer_erc721(mut self, token: Address, token_id: U256) -> Self {
+        self.offer.push(OfferItem {
+            itemType: ItemType::ERC721 as u8,
+            token,
+            identifierOrCriteria: token_id,
+            startAmount: U256::from(1),
+            endAmount: U256::from(1),
+        });
+        self
+    }
+
+    /// Offers a fixed `amount` of the ERC1155 token `id` of `token`.
+    pub fn offer_erc1155(mut self, token: Address, id: U256, amount: U256) -> Self {
+        self.offer.push(OfferItem {
+            itemType: ItemType::ERC1155 as u8,
+            token,
+            identifierOrCriteria: id,
+            startAmount: amount,
+            endAmount: amount,
+        });
+        self
+    }
+
+    /// Asks for a fixed `amount` of the ERC20 `token`, paid to `recipient`.
+    pub fn consideration_erc20(mut self, token: Address, amount: U256, recipient: Address) -> Self {
+        self.consideration.push(ConsiderationItem {
+            itemType: ItemType::ERC20 as u8,
+            token,
+            identifierOrCriteria: U256::from(0),
+            startAmount: amount,
+            endAmount: amount,
+            recipient,
+        });
+        self
+    }
+
+    /// Makes the last offer item's amount move linearly from `start` to `end` over the
+    /// order's lifetime, as Seaport does when the two differ. Offering more over time
+    /// improves the price for takers.
+    pub fn offer_decay(mut self, start: U256, end: U256) -> Self {
+        match self.offer.last_mut() {
+            Some(item) => {
+                item.startAmount = start;
+                item.endAmount = end;
+            }
+            None => self.misuse = Some("offer_decay called before any offer item was added"),
+        }
+        self
+    }
+
+    /// Makes the last consideration item's amount move linearly from `start` to `end`
+    /// over the order's lifetime. Asking for less over time is a Dutch auction.
+    pub fn consideration_decay(mut self, start: U256, end: U256) -> Self {
+        match self.consideration.last_mut() {
+            Some(item) => {
+                item.startAmount = start;
+                item.endAmount = end;
+            }
+            None => {
+                self.misuse =
+                    Some("consideration_decay called before any consideration item was added")
+            }
+        }
+        self
+    }
+
+    pub fn timespan(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.timespan = Some((start, end));
+        self
+    }
+
+    pub fn salt(mut self, salt: U256) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    pub fn counter(mut self, counter: U256) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn build(self) -> eyre::Result<OrderComponents> {
+        if let Some(misuse) = self.misuse {
+            eyre::bail!(misuse);
+        }
+        if self.offerer == Address::ZERO {
+            eyre::bail!("order has no offerer");
+        }
+        if self.offer.is_empty() {
+            eyre::bail!("order offers nothing");
+        }
+        if has_custom_zone(self.zone) && !is_restricted(self.order_type as u8) {
+            eyre::bail!(
+                "zone {} needs a FULL_RESTRICTED or PARTIAL_RESTRICTED order, not {:?}",
+                self.zone,
+                self.order_type
+            );
+        }
+        let (start_time, end_time) = self.timespan.unwrap_or_else(|| {
+            let now = Timestamp::now();
+            (now, now + Duration::from_millis(DEFAULT_DURATION as u64))
+        });
+        if end_time <= start_time {
+            eyre::bail!(
+                "order ends at {} before it starts at {}",
+                end_time.as_secs(),
+                start_time.as_secs()
+            );
+        }
+        Ok(OrderComponents {
+            offerer: self.offerer,
+            zone: self.zone,
+            offer: self.offer,
+            consideration: self.consideration,
+            orderType: self.order_type as u8,
+            startTime: start_time.into(),
+            endTime: end_time.into(),
+            zoneHash: self.zone_hash,
+            salt: self.salt.unwrap_or_else(random_salt),
+            conduitKey: self.conduit_key,
+            counter: self.counter,
+        })
+    }
+}
+
+/// An order selling `sell_amount` of `sell_token` for `buy_amount` of `buy_token`, paid
+/// to the offerer, between the `start` and `end` timestamps in seconds. Everything else
+/// takes the same defaults as `OrderComponentsBuilder`.
+pub fn erc20_swap_order(
+    offerer: Address,
+    sell_token: Address,
+    sell_amount: U256,
+    buy_token: Address,
+    buy_amount: U256,
+    start: U256,
+    end: U256,
+) -> OrderComponents {
+    OrderComponents {
+        offerer,
+        zone: DEFAULT_ORDER_ADDRESS,
+        offer: vec![OfferItem {
+            itemType: ItemType::ERC20 as u8,
+            token: sell_token,
+            identifierOrCriteria: U256::from(0),
+            startAmount: sell_amount,
+            endAmount: sell_amount,
+        }],
+        consideration: vec![ConsiderationItem {
+            itemType: ItemType::ERC20 as u8,
+            token: buy_token,
+            identifierOrCriteria: U256::from(0),
+            startAmount: buy_amount,
+            endAmount: buy_amount,
+            recipient: offerer,
+        }],
+        orderType: OrderType::PARTIAL_RESTRICTED as u8,
+        startTime: start,
+        endTime: end,
+        zoneHash: DEFAULT_ZONE_HASH.into(),
+        salt: random_salt(),
+        conduitKey: DEFAULT_CONDUIT_KEY.into(),
+        counter: U256::from(0),
+    }
+}
+
+/// Restricts `order` to `taker` the way Seaport private listings do: every offered item
+/// is also owed to `taker` as consideration, so whoever fills the order the items can
+/// only end up with `taker`. `taker` fills it by matching it with `counter_order`.
+pub fn private_to(mut order: OrderComponents, taker: Address) -> OrderComponents {
+    let owed = order
+        .offer
+        .iter()
+        .map(|item| ConsiderationItem {
+            itemType: item.itemType,
+            token: item.token,
+            identifierOrCriteria: item.identifierOrCriteria,
+            startAmount: item.startAmount,
+            endAmount: item.endAmount,
+            recipient: taker,
+        })
+        .collect::<Vec<_>>();
+    order.consideration.extend(owed);
+    order
+}
+
+/// The order a taker signs to fill `resting`: it offers what `resting` asks for and asks
+/// for what `resting` offers, paid to `taker`. Items `resting` already owes to `taker`,
+/// as in a `private_to` order, are left out of both sides. Timing, zone and conduit are
+/// copied so the two orders match; the salt is fresh and the counter is zero.
+pub fn counter_order(resting: &OrderComponents, taker: Address) -> OrderComponents {
+    let owed_to_taker = |item: &OfferItem| {
+        resting.consideration.iter().any(|owed| {
+            owed.recipient == taker
+                && owed.itemType == item.itemType
+                && owed.token == item.token
+                && owed.identifierOrCriteria == item.identifierOrCriteria
+        })
+    };
+    OrderComponents {
+        offerer: taker,
+        zone: resting.zone,
+        offer: resting
+            .consideration
+            .iter()
+            .filter(|item| item.recipient != taker)
+            .map(|item| OfferItem {
+                itemType: item.itemType,
+                token: item.token,
+                identifierOrCriteria: item.identifierOrCriteria,
+                startAmount: item.startAmount,
+                endAmount: item.endAmount,
+            })
+            .collect(),
+        consideration: resting
+            .offer
+            .iter()
+            .filter(|item| !owed_to_taker(item))
+            .map(|item| ConsiderationItem {
+                itemType: item.itemType,
+                token: item.token,
+                identifierOrCriteria: item.identifierOrCriteria,
+                startAmount: item.startAmount,
+                endAmount: item.endAmount,
+                recipient: taker,
+            })
+            .collect(),
+        orderType: resting.orderType,
+        startTime: resting.startTime,
+        endTime: resting.endTime,
+        zoneHash: resting.zoneHash,
+        salt: random_salt(),
+        conduitKey: resting.conduitKey,
+        counter: U256::from(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn builds_with_defaults() {
+        let offerer = address!("1111111111111111111111111111111111111111");
+        let order = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(
+                address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+                U256::from(1000000000000000_u128),
+            )
+            .consideration_erc20(
+                address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+                U256::from(1500000),
+                offerer,
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(order.zone, DEFAULT_ORDER_ADDRESS);
+        assert_eq!(order.zoneHash, FixedBytes::from(DEFAULT_ZONE_HASH));
+        assert_eq!(order.conduitKey, FixedBytes::from(DEFAULT_CONDUIT_KEY));
+        assert_eq!(order.orderType, OrderType::PARTIAL_RESTRICTED as u8);
+        assert_eq!(order.endTime - order.startTime, U256::from(86400));
+        assert_eq!(order.consideration[0].recipient, offerer);
+        assert_ne!(order.salt, U256::from(0));
+
+        let timed = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(Address::ZERO, U256::from(1))
+            .timespan(
+                Timestamp::from_secs(1697240202),
+                Timestamp::from_secs(1697243802),
+            )
+            .salt(U256::from(7))
+            .build()
+            .unwrap();
+        assert_eq!(timed.startTime, U256::from(1697240202));
+        assert_eq!(timed.salt, U256::from(7));
+
+        let swap = erc20_swap_order(
+            offerer,
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000000000000000_u128),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500000),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        let mut built = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(
+                address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+                U256::from(1000000000000000_u128),
+            )
+            .consideration_erc20(
+                address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+                U256::from(1500000),
+                offerer,
+            )
+            .timespan(
+                Timestamp::from_secs(1697240202),
+                Timestamp::from_secs(1697243802),
+            )
+            .salt(swap.salt)
+            .build()
+            .unwrap();
+        assert_eq!(built.to_json(), swap.to_json());
+        built.salt = U256::from(0);
+        assert_ne!(built.to_json(), swap.to_json());
+
+        let auction = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(Address::ZERO, U256::from(1000))
+            .consideration_erc20(Address::ZERO, U256::from(1600), offerer)
+            .consideration_decay(U256::from(1600), U256::from(1500))
+            .build()
+            .unwrap();
+        assert_eq!(auction.consideration[0].startAmount, U256::from(1600));
+        assert_eq!(auction.consideration[0].endAmount, U256::from(1500));
+        assert_eq!(auction.offer[0].endAmount, U256::from(1000));
+        assert!(OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(Address::ZERO, U256::from(1000))
+            .consideration_decay(U256::from(1600), U256::from(1500))
+            .build()
+            .is_err());
+
+        assert!(OrderComponentsBuilder::new().build().is_err());
+        assert!(OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc20(Address::ZERO, U256::from(1))
+            .timespan(
+                Timestamp::from_secs(1697243802),
+                Timestamp::from_secs(1697240202)
+            )
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn custom_zone_needs_restricted_order() {
+        let offerer = address!("1111111111111111111111111111111111111111");
+        let zone = address!("9999999999999999999999999999999999999999");
+        let zone_hash = FixedBytes::from([7u8; 32]);
+        let order = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .zone(zone)
+            .zone_hash(zone_hash)
+            .order_type(OrderType::FULL_RESTRICTED)
+            .offer_erc20(Address::ZERO, U256::from(1))
+            .build()
+            .unwrap();
+        assert_eq!(order.zone, zone);
+        assert_eq!(order.zoneHash, zone_hash);
+
+        assert!(OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .zone(zone)
+            .order_type(OrderType::FULL_OPEN)
+            .offer_erc20(Address::ZERO, U256::from(1))
+            .build()
+            .is_err());
+        assert!(OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .zone(zone)
+            .order_type(OrderType::CONTRACT)
+            .offer_erc20(Address::ZERO, U256::from(1))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn nft_offer_items() {
+        let offerer = address!("1111111111111111111111111111111111111111");
+        let collection = address!("BC4CA0EdA7647A8aB7C2061c2E118A18a936f13D");
+        let order = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .offer_erc721(collection, U256::from(42))
+            .offer_erc1155(collection, U256::from(7), U256::from(3))
+            .consideration_erc20(Address::ZERO, U256::from(1500000), offerer)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.offer[0].itemType, ItemType::ERC721 as u8);
+        assert_eq!(order.offer[0].identifierOrCriteria, U256::from(42));
+        assert_eq!(order.offer[0].startAmount, U256::from(1));
+        assert_eq!(order.offer[0].endAmount, U256::from(1));
+        assert_eq!(order.offer[1].itemType, ItemType::ERC1155 as u8);
+        assert_eq!(order.offer[1].identifierOrCriteria, U256::from(7));
+        assert_eq!(order.offer[1].startAmount, U256::from(3));
+        assert_eq!(order.validate(), Ok(()));
+    }
+
+    #[test]
+    fn counter_order_swaps_sides() {
+        let maker = address!("1111111111111111111111111111111111111111");
+        let taker = address!("2222222222222222222222222222222222222222");
+        let resting = erc20_swap_order(
+            maker,
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000000000000000_u128),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500000),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        let taking = counter_order(&resting, taker);
+
+        assert_eq!(taking.offerer, taker);
+        assert_eq!(taking.offer[0].token, resting.consideration[0].token);
+        assert_eq!(taking.offer[0].startAmount, U256::from(1500000));
+        assert_eq!(taking.consideration[0].token, resting.offer[0].token);
+        assert_eq!(taking.consideration[0].recipient, taker);
+        assert_eq!(taking.endTime, resting.endTime);
+        assert_ne!(taking.salt, resting.salt);
+    }
+
+    #[test]
+    fn private_order_owes_offer_to_taker() {
+        let maker = address!("1111111111111111111111111111111111111111");
+        let taker = address!("2222222222222222222222222222222222222222");
+        let public = erc20_swap_order(
+            maker,
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000000000000000_u128),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500000),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        let private = private_to(public.clone(), taker);
+
+        assert_eq!(private.to_json()["offer"], public.to_json()["offer"]);
+        assert_eq!(private.consideration.len(), 2);
+        assert_eq!(
+            private.consideration[0].to_json(),
+            public.consideration[0].to_json()
+        );
+        let owed = &private.consideration[1];
+        assert_eq!(owed.token, public.offer[0].token);
+        assert_eq!(owed.startAmount, public.offer[0].startAmount);
+        assert_eq!(owed.recipient, taker);
+        assert_eq!(private.validate(), Ok(()));
+
+        // The taker only pays; the offered tokens already come to it as consideration.
+        let taking = counter_order(&private, taker);
+        assert_eq!(taking.offer.len(), 1);
+        assert_eq!(taking.offer[0].token, public.consideration[0].token);
+        assert_eq!(taking.offer[0].startAmount, U256::from(1500000));
+        assert!(taking.consideration.is_empty());
+    }
+}
@@ -11,3 +11,32 @@ pub static DEFAULT_CONDUIT_KEY: [u8; 32] =
 
 pub static REQUEST_URL: &str = "wss://api.beta.order.aori.io";
 pub static MARKET_FEED_URL: &str = "wss://beta.feed.aori.io";
+
+/// Chains with a Seaport 1.5 deployment at `CURRENT_SEAPORT_ADDRESS`: Ethereum, Goerli,
+/// Sepolia, Optimism, BNB Chain, Gnosis, Polygon, Base, Arbitrum One, Arbitrum Nova,
+/// Avalanche and Zora.
+pub static SEAPORT_CHAIN_IDS: [u64; 12] = [
+    1, 5, 11155111, 10, 56, 100, 137, 8453, 42161, 42170, 43114, 7777777,
+];
+
+/// Address of the Seaport deployment (version `CURRENT_SEAPORT_VERSION`) on `chain_id`,
+/// or `None` for chains it isn't known to be deployed on. Use
+/// `seaport::seaport_domain_for` for deployments at other addresses.
+pub fn seaport_address(chain_id: u64) -> Option<Address> {
+    SEAPORT_CHAIN_IDS
+        .contains(&chain_id)
+        .then_some(CURRENT_SEAPORT_ADDRESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_seaport_chains() {
+        assert_eq!(seaport_address(1), Some(CURRENT_SEAPORT_ADDRESS));
+        assert_eq!(seaport_address(42161), Some(CURRENT_SEAPORT_ADDRESS));
+        assert_eq!(seaport_address(8453), Some(CURRENT_SEAPORT_ADDRESS));
+        assert_eq!(seaport_address(31337), None);
+    }
+}
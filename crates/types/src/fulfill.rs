@@ -0,0 +1,234 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use ethers::types::Signature;
+
+use crate::constants::DEFAULT_CONDUIT_KEY;
+use crate::seaport::{
+    fulfillAdvancedOrderCall, fulfillBasicOrderCall, AdditionalRecipient, AdvancedOrder,
+    BasicOrderParameters, BasicOrderType, ConsiderationItem, ItemType, OrderComponents,
+    OrderParameters, OrderType,
+};
+
+/// Calldata for Seaport's `fulfillAdvancedOrder`, filling `numerator / denominator` of
+/// `order` (1/1 for all of it) and sending the offered items to `recipient`. Submit it
+/// to the Seaport deployment through any provider; the fulfiller approves Seaport
+/// directly rather than through a conduit.
+pub fn fulfill_advanced_order_calldata(
+    order: &OrderComponents,
+    signature: &Signature,
+    numerator: u128,
+    denominator: u128,
+    recipient: Address,
+) -> eyre::Result<Vec<u8>> {
+    order.validate()?;
+    if numerator == 0 || numerator > denominator {
+        eyre::bail!("invalid fill fraction {}/{}", numerator, denominator);
+    }
+    let call = fulfillAdvancedOrderCall {
+        advancedOrder: AdvancedOrder {
+            parameters: OrderParameters::try_from(order)?,
+            numerator,
+            denominator,
+            signature: signature.to_vec().into(),
+            extraData: Default::default(),
+        },
+        criteriaResolvers: vec![],
+        fulfillerConduitKey: DEFAULT_CONDUIT_KEY.into(),
+        recipient,
+    };
+    Ok(call.abi_encode())
+}
+
+/// Maps a two-party order onto Seaport's cheaper basic-order path: one offer item, the
+/// first consideration item paid to the offerer, and any further consideration items
+/// (fees, royalties) paid in the same currency to other recipients. Errors for orders
+/// the basic path can't express, such as ERC20-for-ERC20 swaps, criteria items,
+/// decaying amounts or contract orders.
+pub fn to_basic_order_parameters(
+    order: &OrderComponents,
+    signature: &Signature,
+) -> eyre::Result<BasicOrderParameters> {
+    order.validate()?;
+    let [offer] = order.offer.as_slice() else {
+        eyre::bail!(
+            "basic orders offer exactly one item, not {}",
+            order.offer.len()
+        );
+    };
+    let (first, additional) = order
+        .consideration
+        .split_first()
+        .ok_or_else(|| eyre::eyre!("basic orders need a consideration item"))?;
+    if first.recipient != order.offerer {
+        eyre::bail!("the first consideration item of a basic order must pay the offerer");
+    }
+    if offer.startAmount != offer.endAmount
+        || order
+            .consideration
+            .iter()
+            .any(|item| item.startAmount != item.endAmount)
+    {
+        eyre::bail!("basic orders can't have decaying amounts");
+    }
+    if order.orderType > OrderType::PARTIAL_RESTRICTED as u8 {
+        eyre::bail!("contract orders can't use the basic order path");
+    }
+
+    const NATIVE: u8 = ItemType::NATIVE as u8;
+    const ERC20: u8 = ItemType::ERC20 as u8;
+    const ERC721: u8 = ItemType::ERC721 as u8;
+    const ERC1155: u8 = ItemType::ERC1155 as u8;
+    // Routes in `BasicOrderType` order, each spanning the four order types. Fee items
+    // are paid in whatever currency the route uses.
+    let (route, fee_type, fee_token) = match (first.itemType, offer.itemType) {
+        (NATIVE, ERC721) => (0, NATIVE, first.token),
+        (NATIVE, ERC1155) => (1, NATIVE, first.token),
+        (ERC20, ERC721) => (2, ERC20, first.token),
+        (ERC20, ERC1155) => (3, ERC20, first.token),
+        (ERC721, ERC20) => (4, ERC20, offer.token),
+        (ERC1155, ERC20) => (5, ERC20, offer.token),
+        (consideration, offered) => eyre::bail!(
+            "no basic order route pays item type {} for item type {}",
+            consideration,
+            offered
+        ),
+    };
+    let fee_matches =
+        |item: &ConsiderationItem| item.itemType == fee_type && item.token == fee_token;
+    if !additional.iter().all(fee_matches) {
+        eyre::bail!("additional consideration items must be paid in the order's currency");
+    }
+    let basic_order_type = BasicOrderType::try_from(route * 4 + order.orderType)
+        .map_err(|err| eyre::eyre!("invalid basic order type: {}", err))?;
+
+    Ok(BasicOrderParameters {
+        considerationToken: first.token,
+        considerationIdentifier: first.identifierOrCriteria,
+        considerationAmount: first.startAmount,
+        offerer: order.offerer,
+        zone: order.zone,
+        offerToken: offer.token,
+        offerIdentifier: offer.identifierOrCriteria,
+        offerAmount: offer.startAmount,
+        basicOrderType: basic_order_type,
+        startTime: order.startTime,
+        endTime: order.endTime,
+        zoneHash: order.zoneHash,
+        salt: order.salt,
+        offererConduitKey: order.conduitKey,
+        fulfillerConduitKey: DEFAULT_CONDUIT_KEY.into(),
+        totalOriginalAdditionalRecipients: U256::from(additional.len()),
+        additionalRecipients: additional
+            .iter()
+            .map(|item| AdditionalRecipient {
+                amount: item.startAmount,
+                recipient: item.recipient,
+            })
+            .collect(),
+        signature: signature.to_vec().into(),
+    })
+}
+
+/// Calldata for Seaport's `fulfillBasicOrder` with the parameters from
+/// `to_basic_order_parameters`.
+pub fn fulfill_basic_order_calldata(
+    order: &OrderComponents,
+    signature: &Signature,
+) -> eyre::Result<Vec<u8>> {
+    let parameters = to_basic_order_parameters(order, signature)?;
+    Ok(fulfillBasicOrderCall { parameters }.abi_encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{erc20_swap_order, OrderComponentsBuilder};
+    use alloy_primitives::address;
+
+    #[test]
+    fn encodes_fulfill_advanced_order() {
+        let order = erc20_swap_order(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        let signature = Signature {
+            r: 1.into(),
+            s: 2.into(),
+            v: 27,
+        };
+        let recipient = address!("2222222222222222222222222222222222222222");
+
+        let calldata =
+            fulfill_advanced_order_calldata(&order, &signature, 1, 2, recipient).unwrap();
+        assert_eq!(calldata[..4], fulfillAdvancedOrderCall::SELECTOR);
+        let call = fulfillAdvancedOrderCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.recipient, recipient);
+        assert_eq!(call.advancedOrder.numerator, 1);
+        assert_eq!(call.advancedOrder.denominator, 2);
+        assert_eq!(call.advancedOrder.signature.len(), 65);
+        assert_eq!(call.advancedOrder.parameters.salt, order.salt);
+        assert_eq!(
+            call.advancedOrder
+                .parameters
+                .totalOriginalConsiderationItems,
+            U256::from(1)
+        );
+
+        assert!(fulfill_advanced_order_calldata(&order, &signature, 3, 2, recipient).is_err());
+    }
+
+    #[test]
+    fn maps_nft_sale_to_basic_order() {
+        let offerer = address!("1111111111111111111111111111111111111111");
+        let marketplace = address!("3333333333333333333333333333333333333333");
+        let usdc = address!("D3664B5e72B46eaba722aB6f43c22dBF40181954");
+        let sale = OrderComponentsBuilder::new()
+            .offerer(offerer)
+            .order_type(OrderType::FULL_RESTRICTED)
+            .offer_erc721(
+                address!("BC4CA0EdA7647A8aB7C2061c2E118A18a936f13D"),
+                U256::from(42),
+            )
+            .consideration_erc20(usdc, U256::from(975), offerer)
+            .consideration_erc20(usdc, U256::from(25), marketplace)
+            .build()
+            .unwrap();
+        let signature = Signature {
+            r: 1.into(),
+            s: 2.into(),
+            v: 27,
+        };
+
+        let basic = to_basic_order_parameters(&sale, &signature).unwrap();
+        assert_eq!(
+            basic.basicOrderType as u8,
+            BasicOrderType::ERC20_TO_ERC721_FULL_RESTRICTED as u8
+        );
+        assert_eq!(basic.offerIdentifier, U256::from(42));
+        assert_eq!(basic.offerAmount, U256::from(1));
+        assert_eq!(basic.considerationToken, usdc);
+        assert_eq!(basic.considerationAmount, U256::from(975));
+        assert_eq!(basic.totalOriginalAdditionalRecipients, U256::from(1));
+        assert_eq!(basic.additionalRecipients[0].recipient, marketplace);
+        assert_eq!(basic.additionalRecipients[0].amount, U256::from(25));
+        let calldata = fulfill_basic_order_calldata(&sale, &signature).unwrap();
+        assert_eq!(calldata[..4], fulfillBasicOrderCall::SELECTOR);
+
+        // Token-for-token swaps have no basic route.
+        let swap = erc20_swap_order(
+            offerer,
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000),
+            usdc,
+            U256::from(1500),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        assert!(to_basic_order_parameters(&swap, &signature).is_err());
+    }
+}
@@ -1,5 +1,14 @@
+pub mod builder;
 pub mod constants;
+pub mod fulfill;
+pub mod permit;
+pub mod revert;
+pub mod salt;
 pub mod seaport;
+pub mod template;
+pub mod time;
+pub mod units;
+pub mod validate;
 
 use ethers::prelude::abigen;
 
@@ -0,0 +1,77 @@
+use alloy_sol_macro::sol;
+
+use alloy_sol_types::{eip712_domain, Eip712Domain};
+
+use alloy_primitives::Address;
+
+use serde_json::{json, Value};
+
+sol! {
+    #[derive(Debug)]
+    struct Permit {
+        address owner;
+        address spender;
+        uint256 value;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+/// EIP-2612 domain of a permit-enabled token. Only tokens that implement `permit`
+/// can be used this way, and `name`/`version` must match the token's own EIP-712
+/// domain exactly (e.g. USDC uses "USD Coin" and "2") or the permit will revert.
+pub fn permit_domain(name: &str, version: &str, chain_id: u64, token: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: String::from(name),
+        version: String::from(version),
+        chain_id: chain_id,
+        verifying_contract: token,
+    }
+}
+
+impl Permit {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "owner": format!("{}", self.owner),
+            "spender": format!("{}", self.spender),
+            "value": format!("{}", self.value),
+            "nonce": format!("{}", self.nonce),
+            "deadline": format!("{}", self.deadline)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CURRENT_SEAPORT_ADDRESS;
+    use alloy_primitives::{address, U256};
+    use alloy_sol_types::SolStruct;
+    use ethers::{
+        signers::{LocalWallet, Signer},
+        types::H256,
+    };
+
+    #[test]
+    fn permit_signature_recovers_owner() {
+        let wallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap();
+        let permit = Permit {
+            owner: Address::from_slice(wallet.address().as_bytes()),
+            spender: CURRENT_SEAPORT_ADDRESS,
+            value: U256::from(1500000),
+            nonce: U256::from(0),
+            deadline: U256::from(1697240202),
+        };
+        let usdc = permit_domain(
+            "USD Coin",
+            "2",
+            1,
+            address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        );
+        let digest = H256::from_slice(permit.eip712_signing_hash(&usdc).as_slice());
+        let sig = wallet.sign_hash(digest).unwrap();
+        assert_eq!(sig.recover(digest).unwrap(), wallet.address());
+    }
+}
@@ -0,0 +1,148 @@
+use alloy_primitives::hex;
+
+/// Seaport 1.5 custom error selectors and what they mean for the caller.
+pub static SEAPORT_ERRORS: [([u8; 4], &str, &str); 20] = [
+    (
+        hex!("8baa579f"),
+        "InvalidSignature",
+        "order signature does not match the offerer",
+    ),
+    (
+        hex!("815e1d64"),
+        "InvalidSigner",
+        "recovered signer is not the offerer",
+    ),
+    (
+        hex!("1f003d0a"),
+        "BadSignatureV",
+        "signature has an invalid v value",
+    ),
+    (
+        hex!("10fda3e1"),
+        "OrderAlreadyFilled",
+        "order has already been fully filled",
+    ),
+    (
+        hex!("1a515574"),
+        "OrderIsCancelled",
+        "order has been cancelled",
+    ),
+    (
+        hex!("ee9e0e63"),
+        "OrderPartiallyFilled",
+        "order is partially filled and can't be filled in full",
+    ),
+    (
+        hex!("21ccfeb7"),
+        "InvalidTime",
+        "order is not active yet or has expired",
+    ),
+    (
+        hex!("8ffff980"),
+        "InsufficientNativeTokensSupplied",
+        "not enough native tokens sent with the call",
+    ),
+    (
+        hex!("12d3f5a3"),
+        "InvalidNativeOfferItem",
+        "native tokens can't be offered in this fulfillment",
+    ),
+    (
+        hex!("5a052b32"),
+        "BadFraction",
+        "requested fill fraction is invalid",
+    ),
+    (
+        hex!("c63cf089"),
+        "InexactFraction",
+        "fill fraction does not divide the item amounts exactly",
+    ),
+    (
+        hex!("a11b63ff"),
+        "PartialFillsNotEnabledForOrder",
+        "order type does not allow partial fills",
+    ),
+    (
+        hex!("a5f54208"),
+        "ConsiderationNotMet",
+        "a consideration item was not fully paid",
+    ),
+    (
+        hex!("466aa616"),
+        "MissingOriginalConsiderationItems",
+        "totalOriginalConsiderationItems exceeds the consideration length",
+    ),
+    (
+        hex!("d5da9a1b"),
+        "NoSpecifiedOrdersAvailable",
+        "none of the specified orders could be fulfilled",
+    ),
+    (
+        hex!("fb5014fc"),
+        "InvalidRestrictedOrder",
+        "zone rejected the restricted order",
+    ),
+    (
+        hex!("93979285"),
+        "InvalidContractOrder",
+        "contract offerer rejected the order",
+    ),
+    (
+        hex!("1cf99b26"),
+        "InvalidConduit",
+        "conduit key does not resolve to a valid conduit",
+    ),
+    (
+        hex!("fed398fc"),
+        "CannotCancelOrder",
+        "caller is neither the offerer nor the zone",
+    ),
+    (
+        hex!("39f3e3fd"),
+        "InvalidBasicOrderParameterEncoding",
+        "basic order calldata is malformed",
+    ),
+];
+
+/// Looks up the Seaport custom error that `revert_data` starts with, returning its
+/// name and a description.
+pub fn decode_seaport_error(revert_data: &[u8]) -> Option<(&'static str, &'static str)> {
+    let selector = revert_data.get(..4)?;
+    SEAPORT_ERRORS
+        .iter()
+        .find(|(known, _, _)| known.as_slice() == selector)
+        .map(|(_, name, message)| (*name, *message))
+}
+
+/// Human-readable reason for a Seaport revert, falling back to the raw revert data
+/// when the selector isn't a known Seaport error.
+pub fn seaport_revert_reason(revert_data: &[u8]) -> String {
+    match decode_seaport_error(revert_data) {
+        Some((name, message)) => format!("{name}: {message}"),
+        None => format!("unknown revert 0x{}", hex::encode(revert_data)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_known_selector() {
+        let order_hash = [0xab_u8; 32];
+        let revert_data = [hex!("10fda3e1").as_slice(), order_hash.as_slice()].concat();
+        assert_eq!(
+            decode_seaport_error(&revert_data),
+            Some(("OrderAlreadyFilled", "order has already been fully filled"))
+        );
+        assert_eq!(
+            seaport_revert_reason(&hex!("8baa579f")),
+            "InvalidSignature: order signature does not match the offerer"
+        );
+        assert_eq!(
+            seaport_revert_reason(&hex!("deadbeef")),
+            "unknown revert 0xdeadbeef"
+        );
+        assert_eq!(decode_seaport_error(&[0x8b, 0xaa]), None);
+    }
+}
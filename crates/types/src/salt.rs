@@ -0,0 +1,75 @@
+use alloy_primitives::U256;
+
+use ethers::core::rand::{rngs::StdRng, thread_rng, RngCore, SeedableRng};
+
+/// Where order salts come from.
+pub trait SaltSource {
+    fn next_salt(&mut self) -> U256;
+}
+
+/// Salts drawn from the thread-local CSPRNG; what live orders should use.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomSalt;
+
+impl SaltSource for RandomSalt {
+    fn next_salt(&mut self) -> U256 {
+        let mut bytes = [0u8; 32];
+        thread_rng().fill_bytes(&mut bytes);
+        U256::from_be_bytes(bytes)
+    }
+}
+
+/// A fresh salt from the thread-local CSPRNG, so otherwise identical orders hash
+/// differently.
+pub fn random_salt() -> U256 {
+    RandomSalt.next_salt()
+}
+
+/// A salt that is the same for every call with the same `seed`, for tests.
+pub fn salt_from_seed(seed: u64) -> U256 {
+    SeededSalt::new(seed).next_salt()
+}
+
+/// A reproducible sequence of salts from a fixed seed, for tests and strategies
+/// that need deterministic salts. Not suitable where salts must be unpredictable.
+#[derive(Debug, Clone)]
+pub struct SeededSalt(StdRng);
+
+impl SeededSalt {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl SaltSource for SeededSalt {
+    fn next_salt(&mut self) -> U256 {
+        let mut bytes = [0u8; 32];
+        self.0.fill_bytes(&mut bytes);
+        U256::from_be_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_salts_are_reproducible() {
+        let mut first = SeededSalt::new(42);
+        let mut second = SeededSalt::new(42);
+        let salts: Vec<U256> = (0..4).map(|_| first.next_salt()).collect();
+        assert_eq!(
+            salts,
+            (0..4).map(|_| second.next_salt()).collect::<Vec<_>>()
+        );
+        assert_ne!(salts[0], salts[1]);
+        assert_ne!(SeededSalt::new(7).next_salt(), salts[0]);
+    }
+
+    #[test]
+    fn salt_helpers() {
+        assert_eq!(salt_from_seed(42), SeededSalt::new(42).next_salt());
+        assert_ne!(salt_from_seed(42), salt_from_seed(43));
+        assert_ne!(random_salt(), random_salt());
+    }
+}
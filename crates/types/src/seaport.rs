@@ -1,21 +1,50 @@
 use alloy_sol_macro::sol;
 
-use alloy_sol_types::{eip712_domain, Eip712Domain};
+use alloy_sol_types::{eip712_domain, Eip712Domain, SolStruct};
+
+use alloy_primitives::{Address, FixedBytes, U256};
+
+use ethers::types::{Signature, H256};
 
 use once_cell::sync::Lazy;
 
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
 use serde_json::{json, Value};
 
 use crate::constants::{CURRENT_SEAPORT_ADDRESS, CURRENT_SEAPORT_VERSION};
+use crate::salt::SaltSource;
 
-pub static SEAPORT_DOMAIN: Lazy<Eip712Domain> = Lazy::new(|| {
+/// Seaport domain of the canonical deployment on Goerli.
+#[deprecated(note = "signatures are only valid on Goerli; use `seaport_domain(chain_id)`")]
+pub static SEAPORT_DOMAIN: Lazy<Eip712Domain> = Lazy::new(|| seaport_domain(5));
+
+/// Seaport domain of the canonical deployment on `chain_id`.
+pub fn seaport_domain(chain_id: u64) -> Eip712Domain {
     eip712_domain! {
         name: String::from("Seaport"),
         version: String::from(CURRENT_SEAPORT_VERSION),
-        chain_id: 5,
+        chain_id: chain_id,
         verifying_contract: CURRENT_SEAPORT_ADDRESS,
     }
-});
+}
+
+/// Seaport domain for a deployment at `verifying_contract` on `chain_id`, for forks and
+/// custom Seaport-compatible deployments that don't live at the canonical address.
+pub fn seaport_domain_for(
+    chain_id: u64,
+    verifying_contract: Address,
+) -> eyre::Result<Eip712Domain> {
+    if verifying_contract == Address::ZERO {
+        eyre::bail!("seaport verifying contract must be a non-zero address");
+    }
+    Ok(eip712_domain! {
+        name: String::from("Seaport"),
+        version: String::from(CURRENT_SEAPORT_VERSION),
+        chain_id: chain_id,
+        verifying_contract: verifying_contract,
+    })
+}
 
 sol! {
     #[derive(Debug)]
@@ -168,6 +197,32 @@ sol! {
         address payable recipient;
     }
 
+    #[derive(Debug)]
+    enum Side {
+        OFFER,
+        CONSIDERATION
+    }
+
+    #[derive(Debug)]
+    struct CriteriaResolver {
+        uint256 orderIndex;
+        Side side;
+        uint256 index;
+        uint256 identifier;
+        bytes32[] criteriaProof;
+    }
+
+    function fulfillAdvancedOrder(
+        AdvancedOrder advancedOrder,
+        CriteriaResolver[] criteriaResolvers,
+        bytes32 fulfillerConduitKey,
+        address recipient
+    ) external payable returns (bool fulfilled);
+
+    function fulfillBasicOrder(
+        BasicOrderParameters parameters
+    ) external payable returns (bool fulfilled);
+
     #[derive(Debug)]
     enum BasicOrderType {
         ETH_TO_ERC721_FULL_OPEN,
@@ -257,18 +312,550 @@ impl OrderComponents {
             "counter": format!("{}", self.counter),
         })
     }
+
+    /// Splits the order into `n` child orders whose item amounts add up to this order's,
+    /// with any remainder going to the last child. Each child gets a fresh salt from
+    /// `salts` so the children hash differently.
+    pub fn split(
+        &self,
+        n: usize,
+        salts: &mut impl SaltSource,
+    ) -> eyre::Result<Vec<OrderComponents>> {
+        if n == 0 {
+            eyre::bail!("cannot split an order into zero parts");
+        }
+        let parts = U256::from(n);
+        let mut amounts = self
+            .offer
+            .iter()
+            .flat_map(|item| [item.startAmount, item.endAmount])
+            .chain(
+                self.consideration
+                    .iter()
+                    .flat_map(|item| [item.startAmount, item.endAmount]),
+            );
+        if amounts.any(|amount| amount < parts) {
+            eyre::bail!("order amounts are too small to split into {} parts", n);
+        }
+
+        let share = |amount: U256, i: usize| {
+            let part = amount / parts;
+            if i == n - 1 {
+                amount - part * U256::from(n - 1)
+            } else {
+                part
+            }
+        };
+        Ok((0..n)
+            .map(|i| {
+                let mut child = self.clone();
+                for item in child.offer.iter_mut() {
+                    item.startAmount = share(item.startAmount, i);
+                    item.endAmount = share(item.endAmount, i);
+                }
+                for item in child.consideration.iter_mut() {
+                    item.startAmount = share(item.startAmount, i);
+                    item.endAmount = share(item.endAmount, i);
+                }
+                child.salt = salts.next_salt();
+                child
+            })
+            .collect())
+    }
+}
+
+impl TryFrom<&OrderComponents> for OrderParameters {
+    type Error = eyre::Report;
+
+    /// Drops `counter`, which Seaport looks up on-chain, and counts every consideration
+    /// item as original. Errors if `orderType` isn't a valid `OrderType`.
+    fn try_from(order: &OrderComponents) -> eyre::Result<Self> {
+        let order_type = OrderType::try_from(order.orderType).map_err(|err| {
+            eyre::eyre!("invalid seaport order type {}: {}", order.orderType, err)
+        })?;
+        Ok(OrderParameters {
+            offerer: order.offerer,
+            zone: order.zone,
+            offer: order.offer.clone(),
+            consideration: order.consideration.clone(),
+            orderType: order_type,
+            startTime: order.startTime,
+            endTime: order.endTime,
+            zoneHash: order.zoneHash,
+            salt: order.salt,
+            conduitKey: order.conduitKey,
+            totalOriginalConsiderationItems: U256::from(order.consideration.len()),
+        })
+    }
+}
+
+fn json_field<'a>(value: &'a Value, key: &str) -> eyre::Result<&'a Value> {
+    value
+        .get(key)
+        .ok_or_else(|| eyre::eyre!("missing field `{}`", key))
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> eyre::Result<&'a str> {
+    json_field(value, key)?
+        .as_str()
+        .ok_or_else(|| eyre::eyre!("field `{}` is not a string", key))
+}
+
+/// Reads a uint256 sent as a decimal or `0x` hex string, or as a JSON number.
+fn json_u256(value: &Value, key: &str) -> eyre::Result<U256> {
+    match json_field(value, key)? {
+        Value::String(s) => s
+            .parse()
+            .map_err(|err| eyre::eyre!("field `{}` is not a uint256: {}", key, err)),
+        Value::Number(n) => n
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| eyre::eyre!("field `{}` is not a uint256: {}", key, n)),
+        other => eyre::bail!("field `{}` is not a uint256: {}", key, other),
+    }
+}
+
+fn json_u8(value: &Value, key: &str) -> eyre::Result<u8> {
+    let n: u64 = match json_field(value, key)? {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+    .ok_or_else(|| eyre::eyre!("field `{}` is not a uint8", key))?;
+    u8::try_from(n).map_err(|_| eyre::eyre!("field `{}` is out of range for a uint8", key))
+}
+
+fn json_address(value: &Value, key: &str) -> eyre::Result<Address> {
+    json_str(value, key)?
+        .parse()
+        .map_err(|err| eyre::eyre!("field `{}` is not an address: {}", key, err))
+}
+
+fn json_bytes32(value: &Value, key: &str) -> eyre::Result<FixedBytes<32>> {
+    json_str(value, key)?
+        .parse()
+        .map_err(|err| eyre::eyre!("field `{}` is not a bytes32: {}", key, err))
+}
+
+fn json_items<T>(
+    value: &Value,
+    key: &str,
+    parse: fn(&Value) -> eyre::Result<T>,
+) -> eyre::Result<Vec<T>> {
+    json_field(value, key)?
+        .as_array()
+        .ok_or_else(|| eyre::eyre!("field `{}` is not an array", key))?
+        .iter()
+        .map(parse)
+        .collect()
+}
+
+impl OfferItem {
+    pub fn from_json(value: &Value) -> eyre::Result<Self> {
+        Ok(OfferItem {
+            itemType: json_u8(value, "itemType")?,
+            token: json_address(value, "token")?,
+            identifierOrCriteria: json_u256(value, "identifierOrCriteria")?,
+            startAmount: json_u256(value, "startAmount")?,
+            endAmount: json_u256(value, "endAmount")?,
+        })
+    }
+}
+
+impl ConsiderationItem {
+    pub fn from_json(value: &Value) -> eyre::Result<Self> {
+        Ok(ConsiderationItem {
+            itemType: json_u8(value, "itemType")?,
+            token: json_address(value, "token")?,
+            identifierOrCriteria: json_u256(value, "identifierOrCriteria")?,
+            startAmount: json_u256(value, "startAmount")?,
+            endAmount: json_u256(value, "endAmount")?,
+            recipient: json_address(value, "recipient")?,
+        })
+    }
+}
+
+impl OrderComponents {
+    /// Inverse of `to_json`: reads an order's parameters in the shape Aori sends them,
+    /// with uint256s as decimal or `0x` hex strings and `0x` addresses and bytes32s.
+    /// Extra fields such as `totalOriginalConsiderationItems` are ignored.
+    pub fn from_json(value: &Value) -> eyre::Result<Self> {
+        Ok(OrderComponents {
+            offerer: json_address(value, "offerer")?,
+            zone: json_address(value, "zone")?,
+            offer: json_items(value, "offer", OfferItem::from_json)?,
+            consideration: json_items(value, "consideration", ConsiderationItem::from_json)?,
+            orderType: json_u8(value, "orderType")?,
+            startTime: json_u256(value, "startTime")?,
+            endTime: json_u256(value, "endTime")?,
+            zoneHash: json_bytes32(value, "zoneHash")?,
+            salt: json_u256(value, "salt")?,
+            conduitKey: json_bytes32(value, "conduitKey")?,
+            counter: json_u256(value, "counter")?,
+        })
+    }
+}
+
+// Serde goes through the same JSON shape as `to_json`, which is what Aori sends and
+// expects: camelCase fields, uint256s as strings, hex addresses and bytes32s.
+macro_rules! impl_serde_via_json {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.to_json().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = Value::deserialize(deserializer)?;
+                Self::from_json(&value).map_err(D::Error::custom)
+            }
+        }
+    )*};
+}
+
+impl_serde_via_json!(OfferItem, ConsiderationItem, OrderComponents);
+
+/// The EIP-712 hash of `order` under the canonical Seaport domain on `chain_id`: the
+/// digest that gets signed and the order hash the server reports back.
+pub fn order_hash(order: &OrderComponents, chain_id: u64) -> FixedBytes<32> {
+    order.eip712_signing_hash(&seaport_domain(chain_id))
+}
+
+/// The address that made `signature` over `order` under the canonical Seaport domain on
+/// `chain_id`. A signature over a different order or domain still recovers, to an
+/// unrelated address, so compare the result with the signer you expect.
+pub fn recover_order_signer(
+    order: &OrderComponents,
+    signature: &Signature,
+    chain_id: u64,
+) -> eyre::Result<Address> {
+    let hash = order_hash(order, chain_id);
+    let signer = signature.recover(H256::from_slice(hash.as_slice()))?;
+    Ok(Address::from_slice(signer.as_bytes()))
+}
+
+/// Whether `signature` over `order` under the canonical Seaport domain on `chain_id`
+/// was made by `order.offerer`. Errors only if no signer can be recovered at all.
+pub fn verify_order_signature(
+    order: &OrderComponents,
+    signature: &Signature,
+    chain_id: u64,
+) -> eyre::Result<bool> {
+    Ok(recover_order_signer(order, signature, chain_id)? == order.offerer)
+}
+
+/// Checks that the hash we sign for `order` under `domain` is the hash the server
+/// computed for it. A mismatch almost always means the two sides disagree on the
+/// Seaport domain (chain id or verifying contract), which the server otherwise
+/// reports only as a rejected signature.
+pub fn verify_order_hash(
+    order: &OrderComponents,
+    domain: &Eip712Domain,
+    server_hash: FixedBytes<32>,
+) -> eyre::Result<()> {
+    let local_hash = order.eip712_signing_hash(domain);
+    if local_hash != server_hash {
+        eyre::bail!(
+            "seaport domain mismatch: client hashed order as {} (chain_id: {:?}, verifying_contract: {:?}) but server expects {}",
+            local_hash,
+            domain.chain_id,
+            domain.verifying_contract,
+            server_hash
+        );
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::{DEFAULT_CONDUIT_KEY, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH};
-    use alloy_primitives::{Address, U256};
+    use crate::salt::SeededSalt;
+    use alloy_primitives::address;
+    use ethers::signers::{LocalWallet, Signer};
 
     #[test]
+    #[allow(deprecated)]
     fn load_lazy() {
-        let dom = &*SEAPORT_DOMAIN;
-        println!("{:?}", dom);
+        assert_eq!(SEAPORT_DOMAIN.separator(), seaport_domain(5).separator());
+        assert_ne!(seaport_domain(1).separator(), seaport_domain(5).separator());
+    }
+
+    #[test]
+    fn detects_domain_mismatch() {
+        let order_comps = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        let server_hash = order_comps.eip712_signing_hash(&seaport_domain(5));
+        assert_eq!(order_hash(&order_comps, 5), server_hash);
+        assert_ne!(order_hash(&order_comps, 1), server_hash);
+        assert!(verify_order_hash(&order_comps, &seaport_domain(5), server_hash).is_ok());
+
+        let wrong_domain = eip712_domain! {
+            name: String::from("Seaport"),
+            version: String::from(CURRENT_SEAPORT_VERSION),
+            chain_id: 1,
+            verifying_contract: CURRENT_SEAPORT_ADDRESS,
+        };
+        let err = verify_order_hash(&order_comps, &wrong_domain, server_hash).unwrap_err();
+        assert!(err.to_string().contains("domain mismatch"));
+    }
+
+    #[test]
+    fn verifies_maker_signature() {
+        let wallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap();
+        let order_comps = OrderComponents {
+            offerer: Address::from_slice(wallet.address().as_bytes()),
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        let sig = wallet
+            .sign_hash(H256::from_slice(order_hash(&order_comps, 5).as_slice()))
+            .unwrap();
+        assert!(verify_order_signature(&order_comps, &sig, 5).unwrap());
+        assert!(!verify_order_signature(&order_comps, &sig, 1).unwrap());
+        assert_eq!(
+            recover_order_signer(&order_comps, &sig, 5).unwrap(),
+            order_comps.offerer
+        );
+        assert_ne!(
+            recover_order_signer(&order_comps, &sig, 1).unwrap(),
+            order_comps.offerer
+        );
+
+        let mut spoofed = order_comps;
+        spoofed.offerer = address!("1111111111111111111111111111111111111111");
+        assert!(!verify_order_signature(&spoofed, &sig, 5).unwrap());
+    }
+
+    #[test]
+    fn verifying_contract_override() {
+        let order_comps = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        let canonical = seaport_domain_for(5, CURRENT_SEAPORT_ADDRESS).unwrap();
+        assert_eq!(canonical.separator(), seaport_domain(5).separator());
+
+        let forked =
+            seaport_domain_for(5, address!("1111111111111111111111111111111111111111")).unwrap();
+        assert_ne!(forked.separator(), canonical.separator());
+        assert_ne!(
+            order_comps.eip712_signing_hash(&forked),
+            order_comps.eip712_signing_hash(&canonical)
+        );
+        assert!(seaport_domain_for(5, Address::ZERO).is_err());
+    }
+
+    #[test]
+    fn split_amounts_sum_to_original() {
+        let order_comps = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![OfferItem {
+                itemType: ItemType::ERC20 as u8,
+                token: Address::ZERO,
+                identifierOrCriteria: U256::from(0),
+                startAmount: U256::from(1000),
+                endAmount: U256::from(1000),
+            }],
+            consideration: vec![ConsiderationItem {
+                itemType: ItemType::ERC20 as u8,
+                token: Address::ZERO,
+                identifierOrCriteria: U256::from(0),
+                startAmount: U256::from(1501),
+                endAmount: U256::from(1501),
+                recipient: Address::ZERO,
+            }],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        let children = order_comps.split(3, &mut SeededSalt::new(1)).unwrap();
+        assert_eq!(children.len(), 3);
+
+        let offered = children
+            .iter()
+            .fold(U256::ZERO, |sum, c| sum + c.offer[0].startAmount);
+        let considered = children
+            .iter()
+            .fold(U256::ZERO, |sum, c| sum + c.consideration[0].endAmount);
+        assert_eq!(offered, U256::from(1000));
+        assert_eq!(considered, U256::from(1501));
+        assert_eq!(children[0].consideration[0].startAmount, U256::from(500));
+        assert_eq!(children[2].consideration[0].startAmount, U256::from(501));
+        assert_ne!(children[0].salt, children[1].salt);
+
+        assert!(order_comps.split(0, &mut SeededSalt::new(1)).is_err());
+        assert!(order_comps.split(2000, &mut SeededSalt::new(1)).is_err());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let order_comps = OrderComponents {
+            offerer: address!("1111111111111111111111111111111111111111"),
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![OfferItem {
+                itemType: ItemType::ERC20 as u8,
+                token: address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+                identifierOrCriteria: U256::from(0),
+                startAmount: U256::from(1000000000000000_u128),
+                endAmount: U256::from(1000000000000000_u128),
+            }],
+            consideration: vec![ConsiderationItem {
+                itemType: ItemType::ERC20 as u8,
+                token: address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+                identifierOrCriteria: U256::from(0),
+                startAmount: U256::from(1500000),
+                endAmount: U256::from(1500000),
+                recipient: address!("1111111111111111111111111111111111111111"),
+            }],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697243802),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(42),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(3),
+        };
+        let value = serde_json::to_value(&order_comps).unwrap();
+        assert_eq!(value, order_comps.to_json());
+
+        let parsed: OrderComponents = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(parsed.to_json(), value);
+        assert_eq!(
+            parsed.eip712_hash_struct(),
+            order_comps.eip712_hash_struct()
+        );
+
+        let mut hex_amounts = value.clone();
+        hex_amounts["salt"] = "0x2a".into();
+        hex_amounts["offer"][0]["itemType"] = "1".into();
+        let parsed: OrderComponents = serde_json::from_value(hex_amounts).unwrap();
+        assert_eq!(parsed.salt, U256::from(42));
+        assert_eq!(parsed.offer[0].itemType, ItemType::ERC20 as u8);
+
+        let mut missing = value;
+        missing.as_object_mut().unwrap().remove("counter");
+        let err = serde_json::from_value::<OrderComponents>(missing).unwrap_err();
+        assert!(err.to_string().contains("counter"));
+    }
+
+    #[test]
+    fn from_json_server_order() {
+        let server_order = json!({
+            "offerer": "0x1111111111111111111111111111111111111111",
+            "zone": "0x0000000000000000000000000000000000000000",
+            "offer": [{
+                "itemType": 1,
+                "token": "0x2715Ccea428F8c7694f7e78B2C89cb454c5F7294",
+                "identifierOrCriteria": "0",
+                "startAmount": "1000000000000000",
+                "endAmount": "1000000000000000"
+            }],
+            "consideration": [{
+                "itemType": 1,
+                "token": "0xD3664B5e72B46eaba722aB6f43c22dBF40181954",
+                "identifierOrCriteria": "0",
+                "startAmount": "1500000",
+                "endAmount": "1500000",
+                "recipient": "0x1111111111111111111111111111111111111111"
+            }],
+            "orderType": 3,
+            "startTime": "1697240202",
+            "endTime": "1697243802",
+            "zoneHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "salt": "12",
+            "conduitKey": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "totalOriginalConsiderationItems": 1,
+            "counter": "0"
+        });
+        let order = OrderComponents::from_json(&server_order).unwrap();
+        assert_eq!(
+            order.offer[0].token,
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294")
+        );
+        assert_eq!(order.consideration[0].startAmount, U256::from(1500000));
+        assert_eq!(order.orderType, OrderType::PARTIAL_RESTRICTED as u8);
+        assert_eq!(order.salt, U256::from(12));
+
+        let reparsed = OrderComponents::from_json(&order.to_json()).unwrap();
+        assert_eq!(
+            reparsed.eip712_signing_hash(&seaport_domain(5)),
+            order.eip712_signing_hash(&seaport_domain(5))
+        );
+
+        let mut bad_address = server_order;
+        bad_address["offerer"] = "0x1234".into();
+        assert!(OrderComponents::from_json(&bad_address).is_err());
+    }
+
+    #[test]
+    fn order_parameters_from_components() {
+        let mut order_comps = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![ConsiderationItem {
+                itemType: ItemType::ERC20 as u8,
+                token: Address::ZERO,
+                identifierOrCriteria: U256::from(0),
+                startAmount: U256::from(1),
+                endAmount: U256::from(1),
+                recipient: Address::ZERO,
+            }],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697243802),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(7),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(3),
+        };
+        let converted = OrderParameters::try_from(&order_comps).unwrap();
+        assert_eq!(converted.orderType as u8, order_comps.orderType);
+        assert_eq!(converted.salt, order_comps.salt);
+        assert_eq!(converted.totalOriginalConsiderationItems, U256::from(1));
+
+        // An out-of-range type from feed JSON is an error, not a panic.
+        order_comps.orderType = 5;
+        let err = OrderParameters::try_from(&order_comps).unwrap_err();
+        assert!(err.to_string().contains("invalid seaport order type 5"));
     }
 
     #[test]
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use alloy_primitives::{Address, FixedBytes, U256};
+
+use crate::constants::{
+    DEFAULT_CONDUIT_KEY, DEFAULT_DURATION, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH,
+};
+use crate::salt::random_salt;
+use crate::seaport::{ConsiderationItem, ItemType, OfferItem, OrderComponents, OrderType};
+use crate::time::Timestamp;
+
+/// The fields of an ERC20-for-ERC20 quote that stay fixed from one quote to the next.
+/// `instantiate` fills in the amounts and timing.
+#[derive(Debug, Clone)]
+pub struct OrderTemplate {
+    pub offerer: Address,
+    pub offer_token: Address,
+    pub offer_decimals: u8,
+    pub consideration_token: Address,
+    pub zone: Address,
+    pub zone_hash: FixedBytes<32>,
+    pub conduit_key: FixedBytes<32>,
+    pub order_type: OrderType,
+    pub counter: U256,
+    /// Lifetime of every instantiated order.
+    pub duration: Duration,
+}
+
+impl OrderTemplate {
+    pub fn new(
+        offerer: Address,
+        offer_token: Address,
+        offer_decimals: u8,
+        consideration_token: Address,
+    ) -> Self {
+        Self {
+            offerer,
+            offer_token,
+            offer_decimals,
+            consideration_token,
+            zone: DEFAULT_ORDER_ADDRESS,
+            zone_hash: DEFAULT_ZONE_HASH.into(),
+            conduit_key: DEFAULT_CONDUIT_KEY.into(),
+            order_type: OrderType::PARTIAL_RESTRICTED,
+            counter: U256::from(0),
+            duration: Duration::from_millis(DEFAULT_DURATION as u64),
+        }
+    }
+
+    /// Builds an order offering `size` base units of the offer token at `price`, the
+    /// consideration token base units asked per whole offer token. The order starts now
+    /// and lives for `duration`. Each order gets a fresh random salt, so two
+    /// quotes at the same price and size still hash differently.
+    pub fn instantiate(&self, price: U256, size: U256) -> OrderComponents {
+        let start_time = Timestamp::now();
+        let one_offer_token = U256::from(10).pow(U256::from(self.offer_decimals));
+        let consideration_amount = size * price / one_offer_token;
+        OrderComponents {
+            offerer: self.offerer,
+            zone: self.zone,
+            offer: vec![OfferItem {
+                itemType: ItemType::ERC20 as u8,
+                token: self.offer_token,
+                identifierOrCriteria: U256::from(0),
+                startAmount: size,
+                endAmount: size,
+            }],
+            consideration: vec![ConsiderationItem {
+                itemType: ItemType::ERC20 as u8,
+                token: self.consideration_token,
+                identifierOrCriteria: U256::from(0),
+                startAmount: consideration_amount,
+                endAmount: consideration_amount,
+                recipient: self.offerer,
+            }],
+            orderType: self.order_type as u8,
+            startTime: start_time.into(),
+            endTime: (start_time + self.duration).into(),
+            zoneHash: self.zone_hash,
+            salt: random_salt(),
+            conduitKey: self.conduit_key,
+            counter: self.counter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seaport::order_hash;
+    use alloy_primitives::address;
+
+    #[test]
+    fn instantiate_varies_only_amounts() {
+        let template = OrderTemplate::new(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            18,
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+        );
+        // 1 token at 1500 USDC and 0.5 tokens at 1600 USDC (6 decimals)
+        let first = template.instantiate(
+            U256::from(1_500_000_000_u64),
+            U256::from(1_000_000_000_000_000_000_u128),
+        );
+        let second = template.instantiate(
+            U256::from(1_600_000_000_u64),
+            U256::from(500_000_000_000_000_000_u128),
+        );
+
+        assert_eq!(
+            first.offer[0].startAmount,
+            U256::from(1_000_000_000_000_000_000_u128)
+        );
+        assert_eq!(
+            first.consideration[0].startAmount,
+            U256::from(1_500_000_000_u64)
+        );
+        assert_eq!(
+            second.offer[0].startAmount,
+            U256::from(500_000_000_000_000_000_u128)
+        );
+        assert_eq!(
+            second.consideration[0].startAmount,
+            U256::from(800_000_000_u64)
+        );
+
+        for order in [&first, &second] {
+            assert_eq!(order.offerer, template.offerer);
+            assert_eq!(order.zone, DEFAULT_ORDER_ADDRESS);
+            assert_eq!(order.offer[0].token, template.offer_token);
+            assert_eq!(order.consideration[0].token, template.consideration_token);
+            assert_eq!(order.consideration[0].recipient, template.offerer);
+            assert_eq!(order.orderType, OrderType::PARTIAL_RESTRICTED as u8);
+            assert_eq!(
+                order.endTime - order.startTime,
+                U256::from(template.duration.as_secs())
+            );
+        }
+    }
+
+    #[test]
+    fn instantiate_salts_each_order() {
+        let template = OrderTemplate::new(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            18,
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+        );
+        let price = U256::from(1_500_000_000_u64);
+        let size = U256::from(1_000_000_000_000_000_000_u128);
+        let first = template.instantiate(price, size);
+        let mut second = template.instantiate(price, size);
+        second.startTime = first.startTime;
+        second.endTime = first.endTime;
+
+        assert_ne!(first.salt, second.salt);
+        assert_ne!(order_hash(&first, 5), order_hash(&second, 5));
+    }
+}
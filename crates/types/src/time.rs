@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use alloy_primitives::U256;
+
+use crate::seaport::OrderComponents;
+
+/// Seconds timestamps above this are past the year 5000, while millisecond
+/// timestamps have been above it since 1973. Seaport times are always seconds, so
+/// anything larger was almost certainly passed in milliseconds.
+pub const MAX_SECS_TIMESTAMP: u64 = 100_000_000_000;
+
+/// Millisecond timestamps stay below this until past the year 5000. Order times above it
+/// can't be a date in either unit and are taken as deliberate sentinels, such as an
+/// `endTime` of `NEVER_EXPIRES`.
+pub const MAX_MILLIS_TIMESTAMP: u64 = MAX_SECS_TIMESTAMP * 1000;
+
+/// `endTime` of an order that never expires.
+pub const NEVER_EXPIRES: U256 = U256::MAX;
+
+/// A unix timestamp in seconds, the unit Seaport uses for `startTime`/`endTime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    pub fn now() -> Self {
+        Self(chrono::Utc::now().timestamp() as u64)
+    }
+
+    pub fn from_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    pub fn from_millis(millis: u64) -> Self {
+        Self(millis / 1000)
+    }
+
+    /// Takes a value that is supposed to be in seconds, converting it from
+    /// milliseconds if it is obviously too large to be seconds.
+    pub fn from_secs_lenient(value: u64) -> Self {
+        if value > MAX_SECS_TIMESTAMP {
+            Self::from_millis(value)
+        } else {
+            Self(value)
+        }
+    }
+
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
+
+    pub fn as_millis(&self) -> u64 {
+        self.0 * 1000
+    }
+}
+
+impl std::ops::Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    /// Rounds `rhs` up to whole seconds, so a sub-second duration still moves the
+    /// timestamp forward rather than adding nothing.
+    fn add(self, rhs: Duration) -> Timestamp {
+        let secs = rhs.as_secs() + u64::from(rhs.subsec_nanos() > 0);
+        Timestamp(self.0.saturating_add(secs))
+    }
+}
+
+impl From<Timestamp> for U256 {
+    fn from(ts: Timestamp) -> U256 {
+        U256::from(ts.0)
+    }
+}
+
+/// The timestamp `duration` from now, e.g. for an order's `endTime`.
+pub fn secs_from_now(duration: Duration) -> Timestamp {
+    Timestamp::now() + duration
+}
+
+/// Rejects orders whose `startTime` or `endTime` was given in milliseconds. Times too
+/// large to be milliseconds, like an `endTime` of `NEVER_EXPIRES`, are let through.
+pub fn check_order_timestamps(order: &OrderComponents) -> eyre::Result<()> {
+    let millis = U256::from(MAX_SECS_TIMESTAMP + 1)..=U256::from(MAX_MILLIS_TIMESTAMP);
+    for (field, value) in [("startTime", order.startTime), ("endTime", order.endTime)] {
+        if millis.contains(&value) {
+            eyre::bail!(
+                "order {} of {} looks like milliseconds; Seaport expects seconds",
+                field,
+                value
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{DEFAULT_CONDUIT_KEY, DEFAULT_ORDER_ADDRESS, DEFAULT_ZONE_HASH};
+    use crate::seaport::OrderType;
+    use alloy_primitives::Address;
+
+    #[test]
+    fn timestamp_conversions() {
+        let ts = Timestamp::from_millis(1697240202123);
+        assert_eq!(ts.as_secs(), 1697240202);
+        assert_eq!(ts.as_millis(), 1697240202000);
+        assert_eq!(Timestamp::from_secs(1697240202), ts);
+        let as_u256: U256 = ts.into();
+        assert_eq!(as_u256, U256::from(1697240202));
+        assert_eq!((ts + Duration::from_secs(60)).as_secs(), 1697240262);
+        assert!(secs_from_now(Duration::from_secs(60)) > Timestamp::now());
+        assert_eq!((ts + Duration::from_millis(1)).as_secs(), 1697240203);
+        assert_eq!((ts + Duration::from_millis(60_500)).as_secs(), 1697240263);
+        assert_eq!(
+            (Timestamp::from_secs(u64::MAX) + Duration::from_secs(1)).as_secs(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn millisecond_detection() {
+        assert_eq!(
+            Timestamp::from_secs_lenient(1697240202),
+            Timestamp::from_secs(1697240202)
+        );
+        assert_eq!(
+            Timestamp::from_secs_lenient(1697240202000),
+            Timestamp::from_secs(1697240202)
+        );
+
+        let mut order_comps = OrderComponents {
+            offerer: Address::ZERO,
+            zone: DEFAULT_ORDER_ADDRESS,
+            offer: vec![],
+            consideration: vec![],
+            orderType: OrderType::PARTIAL_RESTRICTED as u8,
+            startTime: U256::from(1697240202),
+            endTime: U256::from(1697240202 + 86400),
+            zoneHash: DEFAULT_ZONE_HASH.into(),
+            salt: U256::from(0),
+            conduitKey: DEFAULT_CONDUIT_KEY.into(),
+            counter: U256::from(0),
+        };
+        assert!(check_order_timestamps(&order_comps).is_ok());
+
+        order_comps.endTime = U256::from(1697240202000_u64 + 86400000);
+        let err = check_order_timestamps(&order_comps).unwrap_err();
+        assert!(err.to_string().contains("endTime"));
+
+        order_comps.endTime = NEVER_EXPIRES;
+        assert!(check_order_timestamps(&order_comps).is_ok());
+        order_comps.endTime = U256::from(u64::MAX);
+        assert!(check_order_timestamps(&order_comps).is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+use alloy_primitives::U256;
+
+/// Formats `amount` base units of a token with `decimals` decimals as a decimal string,
+/// without trailing zeros: 1500000 with 6 decimals is "1.5".
+pub fn to_decimal(amount: U256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parses a decimal string such as "1.5" into base units of a token with `decimals`
+/// decimals. Errors rather than rounding when `value` has more fractional digits than
+/// the token supports.
+pub fn from_decimal(value: &str, decimals: u8) -> eyre::Result<U256> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        eyre::bail!("{:?} is not a decimal amount", value);
+    }
+    let fraction = fraction.trim_end_matches('0');
+    let decimals = usize::from(decimals);
+    if fraction.len() > decimals {
+        eyre::bail!("{} has more than {} decimal places", value, decimals);
+    }
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(U256::ZERO);
+    }
+    digits
+        .parse()
+        .map_err(|err| eyre::eyre!("{} is out of range: {}", value, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_round_trips() {
+        assert_eq!(to_decimal(U256::from(1500000), 6), "1.5");
+        assert_eq!(to_decimal(U256::from(1000000), 6), "1");
+        assert_eq!(to_decimal(U256::from(5), 6), "0.000005");
+        assert_eq!(to_decimal(U256::ZERO, 18), "0");
+        assert_eq!(to_decimal(U256::from(42), 0), "42");
+
+        assert_eq!(from_decimal("1.5", 6).unwrap(), U256::from(1500000));
+        assert_eq!(from_decimal("0.000005", 6).unwrap(), U256::from(5));
+        assert_eq!(from_decimal("2.50", 2).unwrap(), U256::from(250));
+        assert_eq!(from_decimal(".5", 1).unwrap(), U256::from(5));
+        assert_eq!(from_decimal("0", 18).unwrap(), U256::ZERO);
+        assert_eq!(
+            from_decimal("1", 18).unwrap(),
+            U256::from(1_000_000_000_000_000_000_u128)
+        );
+
+        assert!(from_decimal("1.0000001", 6).is_err());
+        assert!(from_decimal("-1", 6).is_err());
+        assert!(from_decimal("1e6", 6).is_err());
+        assert!(from_decimal("", 6).is_err());
+        assert!(from_decimal(".", 6).is_err());
+        assert!(from_decimal(&"9".repeat(80), 0).is_err());
+
+        let amount = U256::from(123456789012345678_u128);
+        assert_eq!(from_decimal(&to_decimal(amount, 18), 18).unwrap(), amount);
+    }
+}
@@ -0,0 +1,213 @@
+use alloy_primitives::{Address, U256};
+
+use thiserror::Error;
+
+use crate::constants::DEFAULT_ORDER_ADDRESS;
+use crate::seaport::{ItemType, OrderComponents, OrderType};
+
+/// Why `OrderComponents::validate` rejected an order.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OrderValidationError {
+    #[error("order has no offerer")]
+    MissingOfferer,
+    #[error("order offers nothing")]
+    EmptyOffer,
+    #[error("order asks for nothing in return")]
+    EmptyConsideration,
+    #[error("order starts at {start} but ends at {end}")]
+    InvalidTimespan { start: U256, end: U256 },
+    #[error("invalid order type {0}")]
+    InvalidOrderType(u8),
+    #[error("zone {zone} needs a FULL_RESTRICTED or PARTIAL_RESTRICTED order, not order type {order_type}")]
+    ZoneOnUnrestrictedOrder { zone: Address, order_type: u8 },
+    #[error("{item} has invalid item type {item_type}")]
+    InvalidItemType { item: String, item_type: u8 },
+    #[error("{item} has a zero amount")]
+    ZeroAmount { item: String },
+    #[error("{item} is fungible but has identifier {identifier}")]
+    IdentifierOnFungible { item: String, identifier: U256 },
+    #[error("{item} is an ERC721 but its amount is not 1")]
+    Erc721AmountNotOne { item: String },
+}
+
+/// Whether `zone` is one the caller chose, rather than none or the Aori default.
+pub(crate) fn has_custom_zone(zone: Address) -> bool {
+    zone != Address::ZERO && zone != DEFAULT_ORDER_ADDRESS
+}
+
+/// Whether Seaport has the zone approve fills of `order_type`. Open orders skip the
+/// zone, and contract orders are generated by the offerer contract instead.
+pub(crate) fn is_restricted(order_type: u8) -> bool {
+    order_type == OrderType::FULL_RESTRICTED as u8
+        || order_type == OrderType::PARTIAL_RESTRICTED as u8
+}
+
+fn check_item(
+    item: String,
+    item_type: u8,
+    identifier: U256,
+    start_amount: U256,
+    end_amount: U256,
+) -> Result<(), OrderValidationError> {
+    if item_type > ItemType::ERC1155_WITH_CRITERIA as u8 {
+        return Err(OrderValidationError::InvalidItemType { item, item_type });
+    }
+    if start_amount == U256::ZERO || end_amount == U256::ZERO {
+        return Err(OrderValidationError::ZeroAmount { item });
+    }
+    let fungible = item_type == ItemType::NATIVE as u8 || item_type == ItemType::ERC20 as u8;
+    if fungible && identifier != U256::ZERO {
+        return Err(OrderValidationError::IdentifierOnFungible { item, identifier });
+    }
+    if item_type == ItemType::ERC721 as u8
+        && (start_amount != U256::from(1) || end_amount != U256::from(1))
+    {
+        return Err(OrderValidationError::Erc721AmountNotOne { item });
+    }
+    Ok(())
+}
+
+impl OrderComponents {
+    /// Catches orders the relayer or Seaport would reject, before they are signed.
+    pub fn validate(&self) -> Result<(), OrderValidationError> {
+        if self.offerer == Address::ZERO {
+            return Err(OrderValidationError::MissingOfferer);
+        }
+        if self.offer.is_empty() {
+            return Err(OrderValidationError::EmptyOffer);
+        }
+        if self.consideration.is_empty() {
+            return Err(OrderValidationError::EmptyConsideration);
+        }
+        if self.startTime >= self.endTime {
+            return Err(OrderValidationError::InvalidTimespan {
+                start: self.startTime,
+                end: self.endTime,
+            });
+        }
+        if self.orderType > OrderType::CONTRACT as u8 {
+            return Err(OrderValidationError::InvalidOrderType(self.orderType));
+        }
+        if has_custom_zone(self.zone) && !is_restricted(self.orderType) {
+            return Err(OrderValidationError::ZoneOnUnrestrictedOrder {
+                zone: self.zone,
+                order_type: self.orderType,
+            });
+        }
+        for (i, item) in self.offer.iter().enumerate() {
+            check_item(
+                format!("offer item {}", i),
+                item.itemType,
+                item.identifierOrCriteria,
+                item.startAmount,
+                item.endAmount,
+            )?;
+        }
+        for (i, item) in self.consideration.iter().enumerate() {
+            check_item(
+                format!("consideration item {}", i),
+                item.itemType,
+                item.identifierOrCriteria,
+                item.startAmount,
+                item.endAmount,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::erc20_swap_order;
+    use alloy_primitives::address;
+
+    #[test]
+    fn rejects_garbage_orders() {
+        let valid = erc20_swap_order(
+            address!("1111111111111111111111111111111111111111"),
+            address!("2715Ccea428F8c7694f7e78B2C89cb454c5F7294"),
+            U256::from(1000),
+            address!("D3664B5e72B46eaba722aB6f43c22dBF40181954"),
+            U256::from(1500),
+            U256::from(1697240202),
+            U256::from(1697243802),
+        );
+        assert_eq!(valid.validate(), Ok(()));
+
+        let mut order = valid.clone();
+        order.offerer = Address::ZERO;
+        assert_eq!(order.validate(), Err(OrderValidationError::MissingOfferer));
+
+        let mut order = valid.clone();
+        order.consideration.clear();
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::EmptyConsideration)
+        );
+
+        let mut order = valid.clone();
+        order.endTime = order.startTime;
+        assert!(matches!(
+            order.validate(),
+            Err(OrderValidationError::InvalidTimespan { .. })
+        ));
+
+        let mut order = valid.clone();
+        order.zone = address!("9999999999999999999999999999999999999999");
+        assert_eq!(order.validate(), Ok(()));
+        order.orderType = OrderType::PARTIAL_OPEN as u8;
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::ZoneOnUnrestrictedOrder {
+                zone: order.zone,
+                order_type: OrderType::PARTIAL_OPEN as u8
+            })
+        );
+        order.orderType = OrderType::CONTRACT as u8;
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::ZoneOnUnrestrictedOrder {
+                zone: order.zone,
+                order_type: OrderType::CONTRACT as u8
+            })
+        );
+        order.zone = DEFAULT_ORDER_ADDRESS;
+        assert_eq!(order.validate(), Ok(()));
+
+        let mut order = valid.clone();
+        order.offer[0].endAmount = U256::ZERO;
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::ZeroAmount {
+                item: "offer item 0".into()
+            })
+        );
+
+        let mut order = valid.clone();
+        order.consideration[0].identifierOrCriteria = U256::from(7);
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::IdentifierOnFungible {
+                item: "consideration item 0".into(),
+                identifier: U256::from(7)
+            })
+        );
+
+        let mut order = valid.clone();
+        order.offer[0].itemType = ItemType::ERC721 as u8;
+        assert_eq!(
+            order.validate(),
+            Err(OrderValidationError::Erc721AmountNotOne {
+                item: "offer item 0".into()
+            })
+        );
+
+        let mut order = valid;
+        order.offer[0].itemType = 9;
+        assert!(matches!(
+            order.validate(),
+            Err(OrderValidationError::InvalidItemType { item_type: 9, .. })
+        ));
+    }
+}